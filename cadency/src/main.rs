@@ -4,10 +4,11 @@ extern crate log;
 extern crate cadency_core;
 
 use cadency_commands::{
-    Anime, Fib, Inspire, Now, Pause, Ping, Play, Resume, Roll, Skip, Slap, Stop, TrackLoop, Tracks,
-    Urban,
+    Anime, Autoplay, Fib, Filter, History, Inspire, Library, Lyrics, Now, Pause, Ping, Play,
+    Playlist, Policy, Previous, Queue, Radio, Resume, Roll, Search, Shuffle, Skip, Slap, Stay,
+    Stop, TrackLoop, Tracks, Urban,
 };
-use cadency_core::Cadency;
+use cadency_core::{guild::GuildSettings, Cadency};
 use settings::CadencySettings;
 
 mod settings;
@@ -20,18 +21,28 @@ async fn main() {
     let settings = CadencySettings::parse();
 
     let commands = setup_commands![
+        Autoplay::default(),
         Fib::default(),
+        Filter::default(),
+        History::default(),
         Inspire::default(),
+        Library::default(),
+        Lyrics::default(),
         Now::default(),
         Pause::default(),
         Ping::default(),
-        Play::new(
-            settings.play.playlist_song_limit,
-            settings.play.song_length_limit
-        ),
+        Play::new(settings.play.playlist_song_limit),
+        Playlist::new(settings.play.playlist_song_limit),
+        Policy::default(),
+        Previous::default(),
+        Queue::new(settings.play.playlist_song_limit),
+        Radio::default(),
         Resume::default(),
+        Search::default(),
+        Shuffle::default(),
         Skip::default(),
         Slap::default(),
+        Stay::default(),
         Stop::default(),
         Tracks::default(),
         Urban::default(),
@@ -42,6 +53,22 @@ async fn main() {
     let cadency = Cadency::builder()
         .token(std::env::var("DISCORD_TOKEN").expect("Discord token to be present"))
         .commands(commands)
+        .guild_settings(GuildSettings {
+            fair_queue: settings.queue.fair_queue,
+            user_queue_limit: settings.queue.user_queue_limit,
+            vote_skip_threshold: settings.queue.vote_skip_threshold,
+            same_channel_only: settings.queue.same_channel_only,
+            empty_channel_timeout: settings.voice.empty_channel_timeout,
+            inactive_timeout: settings.voice.inactive_timeout,
+            song_length_limit: settings.play.song_length_limit,
+            max_queue_duration: settings.queue.max_queue_duration,
+            autoplay: settings.queue.autoplay,
+            now_playing_panel: settings.queue.now_playing_panel,
+            ..Default::default()
+        })
+        .queue_file(settings.voice.queue_file)
+        .playlist_file(settings.play.playlist_file)
+        .library_path(settings.play.library_path)
         .build()
         .expect("To build cadency");
 
@@ -1,9 +1,25 @@
+use std::{path::PathBuf, time::Duration};
+
 const CADENCY_PLAYLIST_SONG_LIMIT_ENV: &str = "CADENCY_PLAYLIST_SONG_LIMIT";
 const CADENCY_SONG_LENGTH_LIMIT_ENV: &str = "CADENCY_SONG_LENGTH_LIMIT";
+const CADENCY_FAIR_QUEUE_ENV: &str = "CADENCY_FAIR_QUEUE";
+const CADENCY_USER_QUEUE_LIMIT_ENV: &str = "CADENCY_USER_QUEUE_LIMIT";
+const CADENCY_VOTE_SKIP_THRESHOLD_ENV: &str = "CADENCY_VOTE_SKIP_THRESHOLD";
+const CADENCY_SAME_CHANNEL_ONLY_ENV: &str = "CADENCY_SAME_CHANNEL_ONLY";
+const CADENCY_EMPTY_CHANNEL_TIMEOUT_ENV: &str = "CADENCY_EMPTY_CHANNEL_TIMEOUT";
+const CADENCY_INACTIVE_TIMEOUT_ENV: &str = "CADENCY_INACTIVE_TIMEOUT";
+const CADENCY_QUEUE_FILE_ENV: &str = "CADENCY_QUEUE_FILE";
+const CADENCY_PLAYLIST_FILE_ENV: &str = "CADENCY_PLAYLIST_FILE";
+const CADENCY_MAX_QUEUE_DURATION_ENV: &str = "CADENCY_MAX_QUEUE_DURATION";
+const CADENCY_LIBRARY_PATH_ENV: &str = "CADENCY_LIBRARY_PATH";
+const CADENCY_AUTOPLAY_ENV: &str = "CADENCY_AUTOPLAY";
+const CADENCY_NOW_PLAYING_PANEL_ENV: &str = "CADENCY_NOW_PLAYING_PANEL";
 
 pub struct PlaySettings {
     pub playlist_song_limit: i32,
-    pub song_length_limit: f32,
+    pub song_length_limit: Option<Duration>,
+    pub playlist_file: Option<PathBuf>,
+    pub library_path: Option<PathBuf>,
 }
 
 impl PlaySettings {
@@ -24,20 +40,164 @@ impl PlaySettings {
                     .expect("Unable to parse '{CADENCY_SONG_LENGTH_LIMIT_ENV}' to a float")
             })
             .unwrap_or(600.00);
+        // A limit of zero allows songs of any length
+        let song_length_limit =
+            (song_length_limit > 0.0).then(|| Duration::from_secs_f32(song_length_limit));
+        // An empty path keeps the saved playlists in memory only
+        let playlist_file = std::env::var(CADENCY_PLAYLIST_FILE_ENV)
+            .unwrap_or_else(|_| "playlists.json".to_string());
+        let playlist_file = (!playlist_file.is_empty()).then(|| PathBuf::from(playlist_file));
+        // The music library is disabled without a folder
+        let library_path = std::env::var(CADENCY_LIBRARY_PATH_ENV)
+            .ok()
+            .filter(|library_path| !library_path.is_empty())
+            .map(PathBuf::from);
         Self {
             playlist_song_limit,
             song_length_limit,
+            playlist_file,
+            library_path,
+        }
+    }
+}
+
+pub struct QueueSettings {
+    pub fair_queue: bool,
+    pub user_queue_limit: Option<usize>,
+    pub vote_skip_threshold: Option<f32>,
+    pub same_channel_only: bool,
+    pub max_queue_duration: Option<Duration>,
+    pub autoplay: bool,
+    pub now_playing_panel: bool,
+}
+
+impl QueueSettings {
+    pub fn parse() -> Self {
+        let fair_queue = std::env::var(CADENCY_FAIR_QUEUE_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<bool>()
+                    .expect("Unable to parse 'CADENCY_FAIR_QUEUE' to a boolean")
+            })
+            .unwrap_or(false);
+        // A limit of zero disables the limit
+        let user_queue_limit = std::env::var(CADENCY_USER_QUEUE_LIMIT_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .expect("Unable to parse 'CADENCY_USER_QUEUE_LIMIT' to an integer")
+            })
+            .filter(|limit| *limit > 0);
+        // A threshold of zero disables vote skipping
+        let vote_skip_threshold = std::env::var(CADENCY_VOTE_SKIP_THRESHOLD_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<f32>()
+                    .expect("Unable to parse 'CADENCY_VOTE_SKIP_THRESHOLD' to a float")
+            })
+            .inspect(|threshold| {
+                assert!(
+                    (0.0..=1.0).contains(threshold),
+                    "'CADENCY_VOTE_SKIP_THRESHOLD' must be between 0 and 1"
+                )
+            })
+            .filter(|threshold| *threshold > 0.0);
+        let same_channel_only = std::env::var(CADENCY_SAME_CHANNEL_ONLY_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<bool>()
+                    .expect("Unable to parse 'CADENCY_SAME_CHANNEL_ONLY' to a boolean")
+            })
+            .unwrap_or(false);
+        // A duration of zero disables the limit
+        let max_queue_duration = std::env::var(CADENCY_MAX_QUEUE_DURATION_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .expect("Unable to parse 'CADENCY_MAX_QUEUE_DURATION' to an integer")
+            })
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs);
+        let autoplay = std::env::var(CADENCY_AUTOPLAY_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<bool>()
+                    .expect("Unable to parse 'CADENCY_AUTOPLAY' to a boolean")
+            })
+            .unwrap_or(false);
+        let now_playing_panel = std::env::var(CADENCY_NOW_PLAYING_PANEL_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<bool>()
+                    .expect("Unable to parse 'CADENCY_NOW_PLAYING_PANEL' to a boolean")
+            })
+            .unwrap_or(false);
+        Self {
+            fair_queue,
+            user_queue_limit,
+            vote_skip_threshold,
+            same_channel_only,
+            max_queue_duration,
+            autoplay,
+            now_playing_panel,
+        }
+    }
+}
+
+pub struct VoiceSettings {
+    pub empty_channel_timeout: Duration,
+    pub inactive_timeout: Duration,
+    pub queue_file: Option<PathBuf>,
+}
+
+impl VoiceSettings {
+    pub fn parse() -> Self {
+        let empty_channel_timeout = std::env::var(CADENCY_EMPTY_CHANNEL_TIMEOUT_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .expect("Unable to parse 'CADENCY_EMPTY_CHANNEL_TIMEOUT' to an integer")
+            })
+            .unwrap_or(300);
+        let inactive_timeout = std::env::var(CADENCY_INACTIVE_TIMEOUT_ENV)
+            .ok()
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .expect("Unable to parse 'CADENCY_INACTIVE_TIMEOUT' to an integer")
+            })
+            .unwrap_or(120);
+        // An empty path disables saving the queues on shutdown
+        let queue_file =
+            std::env::var(CADENCY_QUEUE_FILE_ENV).unwrap_or_else(|_| "queues.json".to_string());
+        let queue_file = (!queue_file.is_empty()).then(|| PathBuf::from(queue_file));
+        Self {
+            empty_channel_timeout: Duration::from_secs(empty_channel_timeout),
+            inactive_timeout: Duration::from_secs(inactive_timeout),
+            queue_file,
         }
     }
 }
 
 pub struct CadencySettings {
     pub play: PlaySettings,
+    pub queue: QueueSettings,
+    pub voice: VoiceSettings,
 }
 
 impl CadencySettings {
     pub fn parse() -> Self {
         let play = PlaySettings::parse();
-        Self { play }
+        let queue = QueueSettings::parse();
+        let voice = VoiceSettings::parse();
+        Self { play, queue, voice }
     }
 }
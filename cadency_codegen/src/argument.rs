@@ -6,15 +6,23 @@ pub(crate) struct Argument {
     pub description: String,
     pub kind: String,
     pub required: bool,
+    /// Name of the subcommand this argument belongs to
+    pub subcommand: Option<String>,
+    /// Discord asks the command for suggestions while the argument is typed
+    pub autocomplete: bool,
 }
 
 impl Argument {
     pub fn new(name: String, description: String, kind: String) -> Self {
+        // Only one subcommand can be used at a time, so they are never required
+        let required = !matches!(kind.as_str(), "SubCommand" | "SubCommandGroup");
         Self {
             name,
             description,
             kind,
-            required: true,
+            required,
+            subcommand: None,
+            autocomplete: false,
         }
     }
 
@@ -53,17 +61,32 @@ impl Argument {
         self.required = false;
     }
 
+    pub fn of_subcommand(&mut self, subcommand: String) {
+        self.subcommand = Some(subcommand);
+    }
+
+    pub fn has_autocomplete(&mut self) {
+        self.autocomplete = true;
+    }
+
     pub fn to_cadency_command_option(&self) -> proc_macro2::TokenStream {
         let name = self.arg_name();
         let description = &self.description;
         let kind_token = self.kind_token();
         let required = self.required;
+        let autocomplete = self.autocomplete;
+        let subcommand = match &self.subcommand {
+            Some(subcommand) => quote! { Some(#subcommand) },
+            None => quote! { None },
+        };
         quote! {
             __CadencyCommandOption {
                 name: #name,
                 description: #description,
                 kind: __CommandOptionType::#kind_token,
-                required: #required
+                required: #required,
+                subcommand: #subcommand,
+                autocomplete: #autocomplete
             }
         }
     }
@@ -73,7 +96,11 @@ impl Argument {
         let arg_rust_type = self.rust_type();
 
         let arg_name = &self.name;
-        let fn_name_ident = Ident::new(&format!("arg_{arg_name}"), self.name.span());
+        let fn_name = match &self.subcommand {
+            Some(subcommand) => format!("arg_{subcommand}_{arg_name}"),
+            None => format!("arg_{arg_name}"),
+        };
+        let fn_name_ident = Ident::new(&fn_name, self.name.span());
 
         let (fn_return_type, value_unwrap) = if self.required {
             (quote! { #arg_rust_type }, quote! {.unwrap()})
@@ -81,16 +108,36 @@ impl Argument {
             (quote! { Option<#arg_rust_type> }, quote! {})
         };
 
+        // Arguments of a subcommand are nested in the options of the subcommand
+        let options = match &self.subcommand {
+            Some(subcommand) => quote! {
+                command
+                    .data
+                    .options
+                    .iter()
+                    .find(|option| option.name == #subcommand)
+                    .and_then(|option| match &option.value {
+                        serenity::model::application::CommandDataOptionValue::SubCommand(options) => Some(options),
+                        _ => None,
+                    })
+                    .into_iter()
+                    .flatten()
+            },
+            None => quote! {
+                command
+                    .data
+                    .options
+                    .iter()
+            },
+        };
+
         // Create a function to extract the argument from the command
         quote! {
             fn #fn_name_ident(
                 &self,
                 command: &serenity::model::application::CommandInteraction
             ) -> #fn_return_type {
-                command
-                    .data
-                    .options
-                    .iter()
+                #options
                     .find(|option| option.name == #arg_name)
                     .map(|option| option.value.to_owned())
                     .map(|value| {
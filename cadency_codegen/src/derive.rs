@@ -64,7 +64,9 @@ fn parse_arguments(
         let mut name: Option<String> = None;
         let mut description: Option<String> = None;
         let mut kind: Option<String> = None;
-        let mut required = true;
+        let mut required: Option<bool> = None;
+        let mut subcommand: Option<String> = None;
+        let mut autocomplete: Option<bool> = None;
 
         let nested = attr
             .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
@@ -126,7 +128,7 @@ fn parse_arguments(
                             if let Expr::Lit(argument_required_lit) = name_value_arg.value {
                                 if let Lit::Bool(argument_required_lit) = argument_required_lit.lit
                                 {
-                                    required = argument_required_lit.value;
+                                    required = Some(argument_required_lit.value);
                                 } else {
                                     return Err(syn::Error::new(
                                         argument_required_lit.lit.span(),
@@ -135,10 +137,40 @@ fn parse_arguments(
                                 }
                             }
                         }
+                        // #[argument(subcommand = "subcommand")]
+                        "subcommand" => {
+                            if let Expr::Lit(argument_subcommand_lit) = name_value_arg.value {
+                                if let Lit::Str(argument_subcommand_lit) =
+                                    argument_subcommand_lit.lit
+                                {
+                                    subcommand = Some(argument_subcommand_lit.value());
+                                } else {
+                                    return Err(syn::Error::new(
+                                        argument_subcommand_lit.lit.span(),
+                                        "Subcommand must be a string",
+                                    ));
+                                }
+                            }
+                        }
+                        // #[argument(autocomplete = true)]
+                        "autocomplete" => {
+                            if let Expr::Lit(argument_autocomplete_lit) = name_value_arg.value {
+                                if let Lit::Bool(argument_autocomplete_lit) =
+                                    argument_autocomplete_lit.lit
+                                {
+                                    autocomplete = Some(argument_autocomplete_lit.value);
+                                } else {
+                                    return Err(syn::Error::new(
+                                        argument_autocomplete_lit.lit.span(),
+                                        "Autocomplete must be a bool",
+                                    ));
+                                }
+                            }
+                        }
                         _ => {
                             return Err(syn::Error::new(
                                 name_value_arg.path.get_ident().unwrap().span(),
-                                "Only 'name', 'description', 'kind' and 'required' are supported",
+                                "Only 'name', 'description', 'kind', 'required', 'subcommand' and 'autocomplete' are supported",
                             ));
                         }
                     }
@@ -148,9 +180,15 @@ fn parse_arguments(
         }
         if let (Some(name), Some(description), Some(kind)) = (name, description, kind) {
             let mut argument = Argument::new(name, description, kind);
-            if !required {
+            if required == Some(false) {
                 argument.is_optional();
             }
+            if let Some(subcommand) = subcommand {
+                argument.of_subcommand(subcommand);
+            }
+            if autocomplete == Some(true) {
+                argument.has_autocomplete();
+            }
             command.add_argument(argument);
         } else {
             return Err(syn::Error::new(
@@ -0,0 +1,55 @@
+use cadency_core::{
+    guild,
+    response::{Response, ResponseBuilder},
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{async_trait, client::Context, model::application::CommandInteraction};
+
+#[derive(CommandBaseline, Default)]
+#[description = "Toggle adding related songs when the queue runs out"]
+#[argument(
+    name = "enabled",
+    description = "Add a related song once the last song of the queue plays",
+    required = false,
+    kind = "Boolean"
+)]
+pub struct Autoplay {}
+
+#[async_trait]
+impl CadencyCommand for Autoplay {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+        let enabled = self.arg_enabled(command);
+        let autoplay = guild::get_guild_states(ctx)
+            .await
+            .update(guild_id, |state| {
+                // Without arguments autoplay is toggled
+                state.settings.autoplay = enabled.unwrap_or(!state.settings.autoplay);
+                state.settings.autoplay
+            });
+
+        let description = if autoplay {
+            // The last song may already be playing
+            let ctx = ctx.clone();
+            tokio::spawn(async move { utils::autoplay::queue_related_track(&ctx, guild_id).await });
+            "✅ **Autoplay enabled**\n\nA related song is added once the last song of the queue plays."
+        } else {
+            "✅ **Autoplay disabled**\n\nThe playback stops once the queue runs out."
+        };
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("♾️ Autoplay")
+            .color(Colour::from_rgb(186, 85, 211)) // Medium orchid
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
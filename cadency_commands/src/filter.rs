@@ -0,0 +1,70 @@
+use cadency_core::{
+    filter::FilterPreset,
+    guild,
+    response::{Response, ResponseBuilder},
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{async_trait, client::Context, model::application::CommandInteraction};
+
+#[derive(CommandBaseline, Default)]
+#[description = "Apply an audio filter to the next tracks"]
+#[argument(
+    name = "preset",
+    description = "Filter preset (off, bassboost, nightcore, vaporwave, 8d)",
+    required = false,
+    kind = "String"
+)]
+pub struct Filter {}
+
+#[async_trait]
+impl CadencyCommand for Filter {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let guild_states = guild::get_guild_states(ctx).await;
+
+        let mut embed = serenity::builder::CreateEmbed::default()
+            .title("🎛️ Audio Filter")
+            .color(Colour::from_rgb(186, 85, 211)); // Medium orchid
+
+        embed = match self.arg_preset(command) {
+            Some(preset_str) => {
+                // The filter is applied to the tracks of every user
+                utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+                let preset: FilterPreset = preset_str
+                    .parse()
+                    .map_err(|err: String| CadencyError::Command { message: err })?;
+                guild_states.update(guild_id, |state| state.settings.filter = preset);
+                if preset == FilterPreset::None {
+                    embed.description(
+                        "✅ **Filter Disabled**\n\nThe next tracks will play without effects.",
+                    )
+                } else {
+                    embed.description(format!(
+                        "✅ **Filter Enabled:** `{preset}`\n\nThe filter is applied to the next tracks."
+                    ))
+                }
+            }
+            None => {
+                let preset = guild_states.get(guild_id, |state| state.settings.filter);
+                let presets = FilterPreset::ALL
+                    .iter()
+                    .map(|preset| format!("`{}`", preset.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                embed.description(format!(
+                    "🎚️ **Current Filter:** `{preset}`\n\n📋 **Available:** {presets}"
+                ))
+            }
+        };
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
@@ -0,0 +1,75 @@
+use cadency_core::{
+    guild,
+    response::{Response, ResponseBuilder},
+    CadencyCommand, CadencyError,
+};
+use serenity::{
+    all::Mentionable, async_trait, client::Context, model::application::CommandInteraction,
+    model::colour::Colour,
+};
+
+/// Amount of finished tracks that are listed
+const HISTORY_DISPLAY_LIMIT: usize = 10;
+
+#[derive(CommandBaseline, Default)]
+#[description = "List the recently played tracks"]
+pub struct History {}
+
+#[async_trait]
+impl CadencyCommand for History {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let history = guild::get_guild_states(ctx)
+            .await
+            .get(guild_id, |state| state.history.clone());
+
+        if history.is_empty() {
+            return Ok(response_builder
+                .message(Some(
+                    "❌ **No tracks have been played yet**\n\nUse `/play` to add some music!"
+                        .to_string(),
+                ))
+                .build()?);
+        }
+
+        let description = history
+            .iter()
+            .rev()
+            .take(HISTORY_DISPLAY_LIMIT)
+            .enumerate()
+            .map(|(index, track)| {
+                let title = track.title.as_deref().unwrap_or("Unknown Title");
+                let title = match &track.url {
+                    Some(url) => format!("[{}]({})", title, url),
+                    None => format!("`{}`", title),
+                };
+                format!(
+                    "**{}.** {}\n👤 {} • 🕒 <t:{}:R>",
+                    index + 1,
+                    title,
+                    track.requester.mention(),
+                    track.finished_at.unix_timestamp()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("📜 Play History")
+            .color(Colour::from_rgb(100, 149, 237)) // Cornflower blue
+            .description(description)
+            .footer(serenity::all::CreateEmbedFooter::new(format!(
+                "Showing the last {} of {} played tracks",
+                history.len().min(HISTORY_DISPLAY_LIMIT),
+                history.len()
+            )));
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
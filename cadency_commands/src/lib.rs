@@ -5,12 +5,22 @@ extern crate log;
 #[macro_use]
 extern crate cadency_codegen;
 
+mod autoplay;
+pub use autoplay::Autoplay;
 mod fib;
 pub use fib::Fib;
+mod filter;
+pub use filter::Filter;
 mod slap;
 pub use slap::Slap;
+mod history;
+pub use history::History;
 mod inspire;
 pub use inspire::Inspire;
+mod library;
+pub use library::Library;
+mod lyrics;
+pub use lyrics::Lyrics;
 mod now;
 pub use now::Now;
 mod pause;
@@ -19,10 +29,26 @@ mod ping;
 pub use ping::Ping;
 mod play;
 pub use play::Play;
+mod playlist;
+pub use playlist::Playlist;
+mod policy;
+pub use policy::Policy;
+mod previous;
+pub use previous::Previous;
+mod queue;
+pub use queue::Queue;
+mod radio;
+pub use radio::Radio;
 mod resume;
 pub use resume::Resume;
+mod search;
+pub use search::Search;
+mod shuffle;
+pub use shuffle::Shuffle;
 mod skip;
 pub use skip::Skip;
+mod stay;
+pub use stay::Stay;
 mod stop;
 pub use stop::Stop;
 mod tracks;
@@ -187,4 +213,30 @@ mod test {
         assert_eq!(second_argument.description, "The target user");
         assert_eq!(second_argument.kind, CommandOptionType::User);
     }
+
+    #[test]
+    fn return_subcommand_options() {
+        use serenity::model::application::CommandOptionType;
+
+        #[derive(cadency_codegen::CommandBaseline)]
+        #[argument(name = "mode", description = "Change the mode", kind = "SubCommand")]
+        #[argument(
+            name = "fast",
+            description = "Go fast",
+            kind = "Boolean",
+            subcommand = "mode"
+        )]
+        struct Test {}
+        let test = Test {};
+        let arguments = test.options();
+        assert_eq!(arguments.len(), 2);
+        let subcommand = arguments.first().unwrap();
+        let sub_option = arguments.get(1).unwrap();
+        assert_eq!(subcommand.kind, CommandOptionType::SubCommand);
+        assert!(!subcommand.required, "Subcommands should never be required");
+        assert_eq!(subcommand.subcommand, None);
+        assert_eq!(sub_option.name, "fast");
+        assert_eq!(sub_option.subcommand, Some("mode"));
+        assert!(sub_option.required);
+    }
 }
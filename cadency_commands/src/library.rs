@@ -0,0 +1,191 @@
+use cadency_core::{
+    library::{self, LibraryTrack},
+    response::{Response, ResponseBuilder},
+    utils, CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{
+    all::Mentionable,
+    async_trait,
+    builder::{AutocompleteChoice, CreateEmbed},
+    client::Context,
+    model::application::CommandInteraction,
+};
+
+/// Discord allows at most 25 suggestions with up to 100 characters
+const MAX_SUGGESTIONS: usize = 25;
+const MAX_SUGGESTION_LENGTH: usize = 100;
+/// Amount of tracks listed by a search
+const MAX_SEARCH_RESULTS: usize = 10;
+
+#[derive(CommandBaseline, Default)]
+#[description = "Search and play the local music library"]
+#[deferred = true]
+#[argument(
+    name = "search",
+    description = "Search the library by title, artist or album",
+    kind = "SubCommand"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "query",
+    description = "Title, artist or album",
+    kind = "String",
+    subcommand = "search",
+    autocomplete = true
+)]
+#[argument(
+    name = "play",
+    description = "Add a track of the library to the queue",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "track",
+    description = "Title, artist or album",
+    kind = "String",
+    subcommand = "play",
+    autocomplete = true
+)]
+pub struct Library {}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_SUGGESTION_LENGTH).collect()
+}
+
+fn describe(track: &LibraryTrack) -> String {
+    let mut description = format!("`{}`", track.label());
+    if let Some(album) = &track.album {
+        description.push_str(&format!(" • {album}"));
+    }
+    if let Some(duration) = track.duration {
+        description.push_str(&format!(
+            " • {}:{:02}",
+            duration.as_secs() / 60,
+            duration.as_secs() % 60
+        ));
+    }
+    description
+}
+
+fn library_embed(description: String) -> CreateEmbed {
+    CreateEmbed::default()
+        .title("📚 Music Library")
+        .color(Colour::from_rgb(160, 82, 45)) // Sienna
+        .description(description)
+}
+
+impl Library {
+    async fn execute_search(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+    ) -> Result<CreateEmbed, CadencyError> {
+        let query = self.arg_search_query(command);
+        let found = library::get_library(ctx)
+            .await
+            .search(&query, MAX_SEARCH_RESULTS);
+        if found.is_empty() {
+            return Err(CadencyError::Command {
+                message: format!("❌ **No tracks found for '{query}'**"),
+            });
+        }
+        Ok(library_embed(
+            found
+                .iter()
+                .enumerate()
+                .map(|(index, track)| format!("{}. {}", index + 1, describe(track)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ))
+    }
+
+    async fn execute_play(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+    ) -> Result<CreateEmbed, CadencyError> {
+        let query = self.arg_play_track(command);
+        let track = library::get_library(ctx)
+            .await
+            .find(&query)
+            .ok_or(CadencyError::Command {
+                message: format!("❌ **No tracks found for '{query}'**"),
+            })?;
+        let (_, call, guild_id) = utils::voice::join(ctx, command).await?;
+        let added = utils::voice::add_song(
+            ctx,
+            call,
+            guild_id,
+            track.url(),
+            true,
+            command.user.id,
+            None,
+        )
+        .await;
+        if let Err(err) = added {
+            error!("❌ Failed to add library track to queue: {err}");
+            return utils::voice::rejection_embed(&err).ok_or(CadencyError::Command {
+                message: "❌ **Couldn't add the track to the queue!**".to_string(),
+            });
+        }
+        Ok(library_embed(format!(
+            "🎵 **Title:** {}\n👤 **Requested by:** {}\n\n✅ **Added to queue!**",
+            describe(&track),
+            command.user.id.mention()
+        )))
+    }
+}
+
+#[async_trait]
+impl CadencyCommand for Library {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        if library::get_library(ctx).await.is_empty() {
+            return Err(CadencyError::Command {
+                message: "❌ **The music library is empty**".to_string(),
+            });
+        }
+        let embed = if self.arg_search(command).is_some() {
+            self.execute_search(ctx, command).await?
+        } else if self.arg_play(command).is_some() {
+            self.execute_play(ctx, command).await?
+        } else {
+            return Err(CadencyError::Command {
+                message: "❌ **Unknown library command**".to_string(),
+            });
+        };
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+
+    async fn autocomplete(
+        &self,
+        ctx: &Context,
+        interaction: &CommandInteraction,
+    ) -> Vec<AutocompleteChoice> {
+        let Some(option) = interaction.data.autocomplete() else {
+            return Vec::new();
+        };
+        library::get_library(ctx)
+            .await
+            .search(option.value, MAX_SUGGESTIONS)
+            .iter()
+            .map(|track| {
+                let name = match &track.album {
+                    Some(album) => format!("{} • {album}", track.label()),
+                    None => track.label(),
+                };
+                // Long paths don't fit, the label finds the track as well
+                let value = if track.id.chars().count() <= MAX_SUGGESTION_LENGTH {
+                    track.id.clone()
+                } else {
+                    truncate(&track.label())
+                };
+                AutocompleteChoice::new(truncate(&name), value)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,175 @@
+use cadency_core::{
+    lyrics::{self, Lyrics as SongLyrics},
+    response::{Response, ResponseBuilder},
+    track::TrackData,
+    utils, CadencyCommand, CadencyError,
+};
+use serenity::{
+    async_trait,
+    builder::{CreateEmbed, CreateEmbedFooter, EditInteractionResponse},
+    client::Context,
+    model::{application::CommandInteraction, colour::Colour},
+};
+use songbird::tracks::TrackHandle;
+use std::time::Duration;
+
+/// Discord allows up to 256 characters in embed titles
+const MAX_TITLE_LENGTH: usize = 200;
+/// How often the synced lyrics follow the playback
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// Responses to interactions can only be edited for 15 minutes
+const SYNC_TIMEOUT: Duration = Duration::from_secs(14 * 60);
+
+#[derive(CommandBaseline, Default)]
+#[description = "Show the lyrics of the current song"]
+#[deferred = true]
+#[argument(
+    name = "synced",
+    description = "Follow the song and highlight the line that is sung",
+    required = false,
+    kind = "Boolean"
+)]
+pub struct Lyrics {}
+
+fn lyrics_embed(title: &str, description: String, footer: Option<String>) -> CreateEmbed {
+    let title: String = title.chars().take(MAX_TITLE_LENGTH).collect();
+    let embed = CreateEmbed::default()
+        .title(format!("📜 Lyrics: {title}"))
+        .color(Colour::from_rgb(106, 90, 205)) // Slate blue
+        .description(description);
+    match footer {
+        Some(footer) => embed.footer(CreateEmbedFooter::new(footer)),
+        None => embed,
+    }
+}
+
+/// Show the line that is sung until the track ends
+async fn follow_track(
+    ctx: Context,
+    command: CommandInteraction,
+    track: TrackHandle,
+    title: String,
+    lyrics: SongLyrics,
+) {
+    let mut interval = tokio::time::interval(SYNC_INTERVAL);
+    // The first tick completes at once, the first view is sent as the response
+    interval.tick().await;
+    let mut last_view = lyrics.synced_view(Duration::ZERO);
+    let following = async {
+        loop {
+            interval.tick().await;
+            let Ok(info) = track.get_info().await else {
+                break;
+            };
+            if info.playing.is_done() {
+                break;
+            }
+            let view = lyrics.synced_view(info.position);
+            if view == last_view {
+                continue;
+            }
+            let edit = EditInteractionResponse::new().embed(lyrics_embed(
+                &title,
+                view.clone(),
+                Some("🔴 Synced with the playback".to_string()),
+            ));
+            if let Err(err) = command.edit_response(&ctx.http, edit).await {
+                error!("❌ Failed to show synced lyrics: {err}");
+                return;
+            }
+            last_view = view;
+        }
+    };
+    // The response can't be edited anymore after the timeout
+    if tokio::time::timeout(SYNC_TIMEOUT, following).await.is_err() {
+        return;
+    }
+    let edit = EditInteractionResponse::new().embed(lyrics_embed(
+        &title,
+        last_view,
+        Some("The song has ended".to_string()),
+    ));
+    if let Err(err) = command.edit_response(&ctx.http, edit).await {
+        debug!("Failed to end synced lyrics: {err}");
+    }
+}
+
+#[async_trait]
+impl CadencyCommand for Lyrics {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let manager = utils::voice::get_songbird(ctx).await;
+        let call = manager.get(guild_id).ok_or(CadencyError::Command {
+            message: "❌ **No active voice session on the server**".to_string(),
+        })?;
+        let track = call
+            .lock()
+            .await
+            .queue()
+            .current()
+            .ok_or(CadencyError::Command {
+                message: "❌ **No song is playing**".to_string(),
+            })?;
+        let track_data = track.data::<TrackData>();
+        let title = track_data.title().unwrap_or("Unknown Song").to_owned();
+        let lyrics = lyrics::find_lyrics(ctx, &track_data)
+            .await
+            .map_err(|err| {
+                error!("❌ Failed to look up lyrics: {err:?}");
+                CadencyError::Command {
+                    message: "❌ **Couldn't look up the lyrics!**".to_string(),
+                }
+            })?
+            .ok_or(CadencyError::Command {
+                message: "❌ **No lyrics found for this song**".to_string(),
+            })?;
+
+        if self.arg_synced(command).unwrap_or(false) {
+            if lyrics.is_synced() {
+                let position = track
+                    .get_info()
+                    .await
+                    .map(|info| info.position)
+                    .unwrap_or_default();
+                let embed = lyrics_embed(
+                    &title,
+                    lyrics.synced_view(position),
+                    Some("🔴 Synced with the playback".to_string()),
+                );
+                tokio::spawn(follow_track(
+                    ctx.clone(),
+                    command.clone(),
+                    track,
+                    title,
+                    lyrics,
+                ));
+                return Ok(response_builder.embeds(vec![embed]).build()?);
+            }
+            debug!("Only plain lyrics are available for '{title}'");
+        }
+
+        let pages = lyrics.pages();
+        let footer = |page: usize| {
+            let mut footer = format!("Page {}/{}", page + 1, pages.len());
+            if self.arg_synced(command).unwrap_or(false) && !lyrics.is_synced() {
+                footer.push_str(" • No synced lyrics available");
+            }
+            footer
+        };
+        let embed = |page: usize| lyrics_embed(&title, pages[page].clone(), Some(footer(page)));
+        if pages.len() == 1 {
+            return Ok(response_builder.embeds(vec![embed(0)]).build()?);
+        }
+
+        let page = utils::pages::turn_pages(ctx, command, "lyrics", pages.len(), embed).await?;
+        // The buttons are removed once the pages can't be turned anymore
+        Ok(response_builder.embeds(vec![embed(page)]).build()?)
+    }
+}
@@ -1,15 +1,54 @@
 use cadency_core::{
+    guild, panel,
     response::{Response, ResponseBuilder},
-    utils, CadencyCommand, CadencyError,
+    track::TrackData,
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
 };
 use serenity::model::colour::Colour;
 use serenity::{async_trait, client::Context, model::application::CommandInteraction};
-use songbird::{input::AuxMetadata, tracks::LoopState};
 
 #[derive(CommandBaseline, Default)]
 #[description = "Shows current song"]
+#[argument(
+    name = "panel",
+    description = "Keep a live panel with playback buttons in the music channel",
+    required = false,
+    kind = "Boolean"
+)]
 pub struct Now {}
 
+impl Now {
+    async fn toggle_panel(
+        ctx: &Context,
+        command: &CommandInteraction,
+        enabled: bool,
+    ) -> Result<serenity::builder::CreateEmbed, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+        guild::get_guild_states(ctx)
+            .await
+            .update(guild_id, |state| {
+                state.settings.now_playing_panel = enabled;
+                // The panel is posted where the music was started, or here if nothing was played yet
+                state.text_channel.get_or_insert(command.channel_id);
+            });
+        let description = if enabled {
+            panel::refresh(ctx, guild_id).await;
+            "✅ **Panel enabled**\n\nThe current song and playback buttons are shown in the music channel."
+        } else {
+            panel::remove(ctx, guild_id).await;
+            "✅ **Panel disabled**\n\nUse `/now` to see the current song."
+        };
+        Ok(serenity::builder::CreateEmbed::default()
+            .title("🎛️ Now Playing Panel")
+            .color(Colour::from_rgb(255, 110, 64)) // Coral
+            .description(description))
+    }
+}
+
 #[async_trait]
 impl CadencyCommand for Now {
     async fn execute<'a>(
@@ -18,6 +57,10 @@ impl CadencyCommand for Now {
         command: &'a mut CommandInteraction,
         response_builder: &'a mut ResponseBuilder,
     ) -> Result<Response, CadencyError> {
+        if let Some(enabled) = self.arg_panel(command) {
+            let embed = Self::toggle_panel(ctx, command, enabled).await?;
+            return Ok(response_builder.embeds(vec![embed]).build()?);
+        }
         let guild_id = command.guild_id.ok_or(CadencyError::Command {
             message: "❌ **This command can only be executed on a server**".to_string(),
         })?;
@@ -30,28 +73,12 @@ impl CadencyCommand for Now {
             message: "❌ **No song is playing**".to_string(),
         })?;
 
-        let metadata = track.data::<AuxMetadata>();
+        let track_data = track.data::<TrackData>();
         let loop_state = track.get_info().await.unwrap().loops;
-
-        let default_title = "Unknown Song".to_string();
-        let title = metadata.title.as_ref().unwrap_or(&default_title);
-        let url = metadata.source_url.as_ref();
-
-        let mut description = format!("🎵 **Now Playing:** `{}`", title);
-
-        if let Some(url) = url {
-            description.push_str(&format!("\n🔗 **Link:** [View Source]({})", url));
-        }
-
-        match loop_state {
-            LoopState::Infinite => {
-                description.push_str("\n🔁 **Loop:** Infinite");
-            }
-            LoopState::Finite(count) if count > 0 => {
-                description.push_str(&format!("\n🔁 **Loop:** {} times remaining", count));
-            }
-            _ => {}
-        }
+        let filter = guild::get_guild_states(ctx)
+            .await
+            .get(guild_id, |state| state.settings.filter);
+        let description = panel::now_playing_description(&track_data, loop_state, filter);
 
         let embed = serenity::builder::CreateEmbed::default()
             .title("🎧 Now Playing")
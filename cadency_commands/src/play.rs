@@ -1,37 +1,221 @@
 use cadency_core::{
-    handler::voice::InactiveHandler,
+    guild,
+    music_link::{self, MusicLink},
     response::{Response, ResponseBuilder},
-    utils, CadencyCommand, CadencyError,
+    track::{QueuedTrack, TrackData},
+    utils::{
+        self,
+        audio::DirectAudio,
+        voice::{EnqueueSummary, UrlKind},
+    },
+    CadencyCommand, CadencyError,
 };
 use reqwest::Url;
 use serenity::model::colour::Colour;
-use serenity::{async_trait, client::Context, model::application::CommandInteraction};
-use songbird::events::Event;
+use serenity::{
+    all::{GuildId, Mentionable},
+    async_trait,
+    builder::{CreateEmbed, CreateEmbedFooter, EditInteractionResponse},
+    client::Context,
+    model::application::CommandInteraction,
+    prelude::Mutex,
+};
+use songbird::Call;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+
+/// How often the response shows the progress of a loading playlist
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
 
 #[derive(CommandBaseline)]
-#[description = "Play a song from Youtube"]
+#[description = "Play a song from Youtube, an url or an audio file"]
 #[deferred = true]
 #[argument(
     name = "query",
     description = "URL or search query like: 'Hey Jude Beatles'",
+    required = false,
     kind = "String"
 )]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "file",
+    description = "Audio file to play",
+    required = false,
+    kind = "Attachment"
+)]
 pub struct Play {
     /// The maximum number of songs that can be added to the queue from a playlist
     playlist_song_limit: i32,
-    /// The maximum length of a single song in seconds
-    song_length_limit: f32,
 }
 
 impl Play {
-    pub fn new(playlist_song_limit: i32, song_length_limit: f32) -> Self {
+    pub fn new(playlist_song_limit: i32) -> Self {
         Self {
             playlist_song_limit,
-            song_length_limit,
         }
     }
 }
 
+/// Start the playback with the first track and add the others in the background.
+/// The response shows the progress until all tracks are added or the queue is cleared.
+pub(crate) async fn enqueue_tracks(
+    ctx: &Context,
+    command: &CommandInteraction,
+    call: Arc<Mutex<Call>>,
+    guild_id: GuildId,
+    mut tracks: Vec<QueuedTrack>,
+    playlist_song_limit: i32,
+) -> CreateEmbed {
+    let song_limit = usize::try_from(playlist_song_limit).unwrap_or_default();
+    let remaining_tracks = tracks.split_off(tracks.len().min(1));
+    let mut summary =
+        utils::voice::add_songs(ctx, call.clone(), guild_id, tracks, song_limit, None).await;
+    summary.pending = remaining_tracks.len();
+
+    let song_length_limit = guild::get_guild_states(ctx)
+        .await
+        .get(guild_id, |state| state.settings.song_length_limit);
+    let footer = match song_length_limit {
+        Some(limit) => format!(
+            "Playlist limit: {} songs, {} seconds per song",
+            playlist_song_limit,
+            limit.as_secs()
+        ),
+        None => format!("Playlist limit: {} songs", playlist_song_limit),
+    };
+    let embed = playlist_embed(&summary, command, &footer);
+    if !remaining_tracks.is_empty() {
+        tokio::spawn(load_playlist(
+            ctx.clone(),
+            command.clone(),
+            call,
+            guild_id,
+            remaining_tracks,
+            song_limit.saturating_sub(summary.added),
+            summary,
+            footer,
+        ));
+    }
+    embed
+}
+
+/// What `/play` adds to the queue
+enum PlayRequest {
+    Song {
+        payload: String,
+        is_url: bool,
+        /// Audio that the url serves directly, it was already detected for the request
+        direct_audio: Option<DirectAudio>,
+    },
+    /// Playlist, set or album that yt-dlp can list
+    Playlist { url: String },
+    /// Tracks of a Spotify or Apple Music link, each is searched on YouTube
+    Linked {
+        url: String,
+        tracks: Vec<QueuedTrack>,
+    },
+}
+
+/// Look up the tracks of a Spotify or Apple Music link
+async fn resolve_music_link(
+    ctx: &Context,
+    command: &CommandInteraction,
+    url: &str,
+    link: &MusicLink,
+) -> Result<Vec<QueuedTrack>, CadencyError> {
+    let linked_tracks = music_link::get_music_link_provider(ctx)
+        .await
+        .tracks(link)
+        .await
+        .map_err(|err| {
+            error!("❌ Failed to look up the tracks of '{url}': {err:?}");
+            CadencyError::Command {
+                message: "❌ **Couldn't load the tracks of the link!**".to_string(),
+            }
+        })?;
+    if linked_tracks.is_empty() {
+        return Err(CadencyError::Command {
+            message: "❌ **No tracks found behind the link**".to_string(),
+        });
+    }
+    Ok(linked_tracks
+        .into_iter()
+        .map(|track| QueuedTrack {
+            title: Some(track.search_query()),
+            url: None,
+            requester: command.user.id,
+            playlist: Some(url.to_string()),
+            duration: track.duration,
+            verified: false,
+        })
+        .collect())
+}
+
+fn playlist_embed(
+    summary: &EnqueueSummary,
+    command: &CommandInteraction,
+    footer: &str,
+) -> CreateEmbed {
+    let mut description = summary.describe();
+    description.push_str(&format!(
+        "\n👤 **Requested by:** {}",
+        command.user.id.mention()
+    ));
+    description.push_str("\n🎵 **Now Playing**");
+    CreateEmbed::default()
+        .title("📋 Playlist Added")
+        .color(Colour::from_rgb(0, 255, 127)) // Spring green
+        .description(description)
+        .footer(CreateEmbedFooter::new(footer))
+}
+
+/// Add the remaining tracks of a playlist and show the progress in the response
+#[allow(clippy::too_many_arguments)]
+async fn load_playlist(
+    ctx: Context,
+    command: CommandInteraction,
+    call: Arc<Mutex<Call>>,
+    guild_id: GuildId,
+    tracks: Vec<QueuedTrack>,
+    song_limit: usize,
+    mut summary: EnqueueSummary,
+    footer: String,
+) {
+    let (progress, mut progress_updates) = watch::channel(EnqueueSummary::default());
+    let loading =
+        utils::voice::add_songs(&ctx, call, guild_id, tracks, song_limit, Some(&progress));
+    tokio::pin!(loading);
+    let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+    // The first tick completes at once, before the first response is sent
+    interval.tick().await;
+    let loaded = loop {
+        tokio::select! {
+            loaded = &mut loading => break loaded,
+            _ = interval.tick() => {
+                if progress_updates.has_changed().unwrap_or(false) {
+                    let mut current = summary.clone();
+                    current.combine(&progress_updates.borrow_and_update());
+                    show_progress(&ctx, &command, &current, &footer).await;
+                }
+            }
+        }
+    };
+    summary.combine(&loaded);
+    show_progress(&ctx, &command, &summary, &footer).await;
+}
+
+async fn show_progress(
+    ctx: &Context,
+    command: &CommandInteraction,
+    summary: &EnqueueSummary,
+    footer: &str,
+) {
+    let edit = EditInteractionResponse::new().embed(playlist_embed(summary, command, footer));
+    if let Err(err) = command.edit_response(&ctx.http, edit).await {
+        error!("❌ Failed to show playlist progress: {err}");
+    }
+}
+
 #[async_trait]
 impl CadencyCommand for Play {
     async fn execute<'a>(
@@ -40,160 +224,223 @@ impl CadencyCommand for Play {
         command: &'a mut CommandInteraction,
         response_builder: &'a mut ResponseBuilder,
     ) -> Result<Response, CadencyError> {
-        let (search_payload, is_url, is_playlist) = {
-            let query = self.arg_query(command);
-            let (is_valid_url, is_playlist): (bool, bool) =
-                Url::parse(&query).ok().map_or((false, false), |valid_url| {
-                    let is_playlist: bool = valid_url
-                        .query_pairs()
-                        .find(|(key, _)| key == "list")
-                        .is_some_and(|_| true);
-                    (true, is_playlist)
-                });
-            (query, is_valid_url, is_playlist)
-        };
-
-        let (manager, call, guild_id) = utils::voice::join(ctx, command).await?;
-
-        let response_builder = if is_playlist {
-            let playlist_items =
-                cadency_yt_playlist::fetch_playlist_songs(search_payload.clone()).unwrap();
-            playlist_items
-                .messages
-                .iter()
-                .for_each(|entry| debug!("🚧 Unable to parse song from playlist: {entry:?}",));
-            let songs = playlist_items.data;
-            let mut amount_added_playlist_songs = 0;
-            let mut amount_total_added_playlist_duration = 0_f32;
-            let mut skipped_by_limit = 0;
-            let mut skipped_unavailable = 0;
-
-            for song in songs {
-                if amount_added_playlist_songs <= self.playlist_song_limit
-                    && song.duration <= self.song_length_limit
-                {
-                    match utils::voice::add_song(ctx, call.clone(), song.url, true).await {
-                        Ok((added_song_meta, _)) => {
-                            amount_added_playlist_songs += 1;
-                            amount_total_added_playlist_duration += song.duration;
-                            debug!("➕ Added song '{:?}' from playlist", added_song_meta.title);
-                        }
-                        Err(err) => {
-                            error!("❌ Failed to add song: {err}");
-                            skipped_unavailable += 1;
+        let request = match (self.arg_query(command), self.arg_file(command)) {
+            (_, Some(attachment_id)) => {
+                let attachment = command
+                    .data
+                    .resolved
+                    .attachments
+                    .get(&attachment_id)
+                    .ok_or(CadencyError::Command {
+                        message: "❌ **The file could not be found**".to_string(),
+                    })?;
+                let is_audio = attachment
+                    .content_type
+                    .as_deref()
+                    .is_some_and(utils::audio::is_audio_content_type);
+                if !is_audio {
+                    return Err(CadencyError::Command {
+                        message: "❌ **The file is no audio file**".to_string(),
+                    });
+                }
+                PlayRequest::Song {
+                    payload: attachment.url.clone(),
+                    is_url: true,
+                    direct_audio: Some(DirectAudio::File),
+                }
+            }
+            (Some(query), None) => match MusicLink::parse(&query) {
+                // Spotify and Apple Music are not playable, their tracks are searched on YouTube
+                Some(link) => {
+                    let mut tracks = resolve_music_link(ctx, command, &query, &link).await?;
+                    if tracks.len() == 1 {
+                        PlayRequest::Song {
+                            payload: tracks.remove(0).title.unwrap_or_default(),
+                            is_url: false,
+                            direct_audio: None,
                         }
+                    } else {
+                        PlayRequest::Linked { url: query, tracks }
                     }
-                } else {
-                    skipped_by_limit += 1;
                 }
+                None => {
+                    let is_url = Url::parse(&query).is_ok();
+                    let url_kind = if is_url {
+                        utils::voice::classify_url(ctx, &query).await
+                    } else {
+                        UrlKind::Track(None)
+                    };
+                    // Playlists, sets and albums of every site that yt-dlp supports
+                    match url_kind {
+                        UrlKind::Playlist => PlayRequest::Playlist { url: query },
+                        UrlKind::Track(direct_audio) => PlayRequest::Song {
+                            payload: query,
+                            is_url,
+                            direct_audio,
+                        },
+                    }
+                }
+            },
+            (None, None) => {
+                return Err(CadencyError::Command {
+                    message: "❌ **Please provide a search query, an url or an audio file**"
+                        .to_string(),
+                })
             }
-            amount_total_added_playlist_duration /= 60_f32;
-            {
-                let mut handler = call.lock().await;
-                handler.remove_all_global_events();
-                handler.add_global_event(
-                    Event::Periodic(std::time::Duration::from_secs(120), None),
-                    InactiveHandler { guild_id, manager },
-                );
-            }
-
-            let mut description = format!(
-                "✅ **Added {} song{} to the queue**\n⏱️ **Total Duration:** {:.1} minutes",
-                amount_added_playlist_songs,
-                if amount_added_playlist_songs == 1 {
-                    ""
-                } else {
-                    "s"
-                },
-                amount_total_added_playlist_duration
-            );
-
-            if skipped_by_limit > 0 {
-                description.push_str(&format!(
-                    "\n⚠️ **Skipped:** {} song{} (exceeded limits)",
-                    skipped_by_limit,
-                    if skipped_by_limit == 1 { "" } else { "s" }
-                ));
-            }
+        };
 
-            if skipped_unavailable > 0 {
-                description.push_str(&format!(
-                    "\n🚫 **Unavailable:** {} song{} (removed or restricted)",
-                    skipped_unavailable,
-                    if skipped_unavailable == 1 { "" } else { "s" }
-                ));
-            }
+        let (_, call, guild_id) = utils::voice::join(ctx, command).await?;
 
-            description.push_str(&format!("\n🎵 **Now Playing**"));
-
-            let embed = serenity::builder::CreateEmbed::default()
-                .title("📋 Playlist Added")
-                .color(Colour::from_rgb(0, 255, 127)) // Spring green
-                .description(description)
-                .footer(serenity::all::CreateEmbedFooter::new(format!(
-                    "Playlist limit: {} songs, {} seconds per song",
-                    self.playlist_song_limit, self.song_length_limit as i32
-                )));
-            response_builder.embeds(vec![embed])
-        } else {
-            let (added_song_meta, _) = utils::voice::add_song(ctx, call.clone(), search_payload.clone(), is_url)
+        let response_builder = match request {
+            PlayRequest::Playlist { url } => {
+                // yt-dlp blocks until the whole playlist is fetched
+                let playlist_url = url.clone();
+                let playlist_items = tokio::task::spawn_blocking(move || {
+                    cadency_yt_playlist::fetch_playlist_songs(playlist_url)
+                })
                 .await
+                .map_err(|err| CadencyError::Runtime(format!("Playlist task failed: {err}")))?
                 .map_err(|err| {
-                    let err_str = format!("{}", err);
-                    error!("❌ Failed to add song to queue: {}", err);
-
-                    let message = if err_str.contains("Video unavailable") || err_str.contains("not available") {
-                        "❌ **Video is unavailable!**\n\nThis video may be private, deleted, or region-restricted."
-                    } else {
-                        "❌ **Couldn't add audio source to the queue!**\n\nPlease check the URL or search query."
-                    };
-
+                    error!("❌ Failed to fetch playlist: {err}");
                     CadencyError::Command {
-                        message: message.to_string(),
+                        message: "❌ **Couldn't load the playlist!**".to_string(),
                     }
                 })?;
-            {
-                let mut handler = call.lock().await;
-                handler.remove_all_global_events();
-                handler.add_global_event(
-                    Event::Periodic(std::time::Duration::from_secs(120), None),
-                    InactiveHandler { guild_id, manager },
-                );
+                playlist_items
+                    .messages
+                    .iter()
+                    .for_each(|entry| debug!("🚧 Unable to parse song from playlist: {entry:?}",));
+                let tracks = playlist_items
+                    .data
+                    .into_iter()
+                    .map(|song| QueuedTrack {
+                        title: song.title,
+                        url: Some(song.url),
+                        requester: command.user.id,
+                        playlist: Some(url.clone()),
+                        duration: song
+                            .duration
+                            .map(|duration| Duration::from_secs_f32(duration.max(0.0))),
+                        verified: true,
+                    })
+                    .collect();
+                let embed = enqueue_tracks(
+                    ctx,
+                    command,
+                    call.clone(),
+                    guild_id,
+                    tracks,
+                    self.playlist_song_limit,
+                )
+                .await;
+                response_builder.embeds(vec![embed])
             }
-
-            let title = added_song_meta
-                .title
-                .as_ref()
-                .map_or("Unknown Title", |title| title);
-            let song_url = if is_url {
-                search_payload.clone()
-            } else {
-                added_song_meta
-                    .source_url
+            PlayRequest::Linked { url, tracks } => {
+                debug!("Search {} tracks of '{url}' on YouTube", tracks.len());
+                let embed = enqueue_tracks(
+                    ctx,
+                    command,
+                    call.clone(),
+                    guild_id,
+                    tracks,
+                    self.playlist_song_limit,
+                )
+                .await;
+                response_builder.embeds(vec![embed])
+            }
+            PlayRequest::Song {
+                payload: search_payload,
+                is_url,
+                direct_audio,
+            } => {
+                let added = if is_url {
+                    utils::voice::add_detected_song(
+                        ctx,
+                        call.clone(),
+                        guild_id,
+                        search_payload.clone(),
+                        direct_audio,
+                        command.user.id,
+                        None,
+                    )
+                    .await
+                } else {
+                    utils::voice::add_song(
+                        ctx,
+                        call.clone(),
+                        guild_id,
+                        search_payload.clone(),
+                        is_url,
+                        command.user.id,
+                        None,
+                    )
+                    .await
+                };
+                let (added_song_meta, track_handle) = match added {
+                    Ok(added) => added,
+                    Err(err) => {
+                        error!("❌ Failed to add song to queue: {}", err);
+                        if let Some(embed) = utils::voice::rejection_embed(&err) {
+                            return Ok(response_builder.embeds(vec![embed]).build()?);
+                        }
+                        let err_str = format!("{}", err);
+                        let message = if err_str.contains("Video unavailable")
+                            || err_str.contains("not available")
+                        {
+                            "❌ **Video is unavailable!**\n\nThis video may be private, deleted, or region-restricted.".to_string()
+                        } else {
+                            "❌ **Couldn't add audio source to the queue!**\n\nPlease check the URL or search query.".to_string()
+                        };
+                        return Err(CadencyError::Command { message });
+                    }
+                };
+                let title = added_song_meta
+                    .title
                     .as_ref()
-                    .map_or("Unknown URL".to_string(), |url| url.to_owned())
-            };
+                    .map_or("Unknown Title", |title| title);
+                let song_url = if is_url {
+                    search_payload.clone()
+                } else {
+                    added_song_meta
+                        .source_url
+                        .as_ref()
+                        .map_or("Unknown URL".to_string(), |url| url.to_owned())
+                };
 
-            let mut description = format!("🎵 **Title:** `{}`", title);
+                let mut description = format!("🎵 **Title:** `{}`", title);
 
-            if song_url != "Unknown URL" {
-                description.push_str(&format!("\n🔗 **Source:** [View on YouTube]({})", song_url));
-            }
+                if song_url.starts_with("http") {
+                    let link_label = if song_url.contains("youtu") {
+                        "View on YouTube"
+                    } else {
+                        "Open Link"
+                    };
+                    description
+                        .push_str(&format!("\n🔗 **Source:** [{}]({})", link_label, song_url));
+                }
 
-            // Add duration if available
-            if let Some(duration) = added_song_meta.duration {
-                let minutes = duration.as_secs() / 60;
-                let seconds = duration.as_secs() % 60;
-                description.push_str(&format!("\n⏱️ **Duration:** {}:{:02}", minutes, seconds));
-            }
+                // Add duration if available, live streams have none
+                if track_handle.data::<TrackData>().live.is_some() {
+                    description.push_str("\n🔴 **Live**");
+                } else if let Some(duration) = added_song_meta.duration {
+                    let minutes = duration.as_secs() / 60;
+                    let seconds = duration.as_secs() % 60;
+                    description.push_str(&format!("\n⏱️ **Duration:** {}:{:02}", minutes, seconds));
+                }
 
-            description.push_str("\n\n✅ **Added to queue and started playing!**");
+                description.push_str(&format!(
+                    "\n👤 **Requested by:** {}",
+                    command.user.id.mention()
+                ));
 
-            let embed = serenity::builder::CreateEmbed::default()
-                .title("🎶 Song Added")
-                .color(Colour::from_rgb(65, 105, 225)) // Royal blue
-                .description(description);
-            response_builder.embeds(vec![embed])
+                description.push_str("\n\n✅ **Added to queue and started playing!**");
+
+                let embed = serenity::builder::CreateEmbed::default()
+                    .title("🎶 Song Added")
+                    .color(Colour::from_rgb(65, 105, 225)) // Royal blue
+                    .description(description);
+                response_builder.embeds(vec![embed])
+            }
         };
         Ok(response_builder.build()?)
     }
@@ -0,0 +1,421 @@
+use cadency_core::{
+    playlist::{self, SavedPlaylist},
+    response::{Response, ResponseBuilder},
+    track::{QueuedTrack, TrackData},
+    utils, CadencyCommand, CadencyError,
+};
+use reqwest::Url;
+use serenity::model::colour::Colour;
+use serenity::{
+    all::Mentionable, async_trait, client::Context, model::application::CommandInteraction,
+};
+
+/// Maximum amount of tracks a saved playlist can hold
+const MAX_PLAYLIST_TRACKS: usize = 200;
+/// Discord allows up to 4096 characters in embed descriptions
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+/// Names are cut in the list so that more playlists fit into it
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(CommandBaseline)]
+#[description = "Save, load and share your own playlists"]
+#[deferred = true]
+#[argument(
+    name = "save",
+    description = "Save the current queue as playlist",
+    kind = "SubCommand"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "name",
+    description = "Name of the playlist",
+    kind = "String",
+    subcommand = "save"
+)]
+#[argument(
+    name = "load",
+    description = "Add a playlist to the queue",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "name",
+    description = "Name of the playlist",
+    kind = "String",
+    subcommand = "load"
+)]
+#[argument(
+    name = "add",
+    description = "Add a track or the current track to a playlist",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "name",
+    description = "Name of the playlist",
+    kind = "String",
+    subcommand = "add"
+)]
+#[argument(
+    name = "query",
+    description = "URL or search query, the current track is added if empty",
+    required = false,
+    kind = "String",
+    subcommand = "add"
+)]
+#[argument(
+    name = "remove",
+    description = "Remove a track from a playlist or the whole playlist",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "name",
+    description = "Name of the playlist",
+    kind = "String",
+    subcommand = "remove"
+)]
+#[argument(
+    name = "position",
+    description = "Position of the track, the whole playlist is removed if empty",
+    required = false,
+    kind = "Integer",
+    subcommand = "remove"
+)]
+#[argument(
+    name = "list",
+    description = "Show your playlists and the shared playlists of the server",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "share",
+    description = "Let other users of the server load your playlist",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "name",
+    description = "Name of the playlist",
+    kind = "String",
+    subcommand = "share"
+)]
+#[argument(
+    name = "shared",
+    description = "Share or stop sharing the playlist",
+    kind = "Boolean",
+    subcommand = "share"
+)]
+pub struct Playlist {
+    /// The maximum number of songs that can be added to the queue from a playlist
+    playlist_song_limit: i32,
+}
+
+impl Playlist {
+    pub fn new(playlist_song_limit: i32) -> Self {
+        Self {
+            playlist_song_limit,
+        }
+    }
+
+    fn not_found(name: &str) -> CadencyError {
+        CadencyError::Command {
+            message: format!("❌ **There is no playlist named '{name}'**"),
+        }
+    }
+
+    async fn execute_save(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let name = self.arg_save_name(command);
+        let manager = utils::voice::get_songbird(ctx).await;
+        let call = manager.get(guild_id).ok_or(CadencyError::Command {
+            message: "❌ **No active voice session on the server**".to_string(),
+        })?;
+        // Uploaded files can't be played anymore once their urls expire
+        let mut tracks = {
+            let handler = call.lock().await;
+            utils::queue::snapshot_queue(handler.queue())
+                .await
+                .without_expiring()
+                .tracks
+        };
+        if tracks.is_empty() {
+            return Err(CadencyError::Command {
+                message: "❌ **There are no tracks in the queue to save**".to_string(),
+            });
+        }
+        tracks.truncate(MAX_PLAYLIST_TRACKS);
+        let amount = tracks.len();
+        playlist::get_playlist_store(ctx)
+            .await
+            .save(SavedPlaylist {
+                name: name.clone(),
+                owner: command.user.id,
+                guild_id,
+                shared: false,
+                tracks,
+            })
+            .await?;
+        Ok(format!(
+            "✅ **Saved playlist `{name}`**\n\n🎵 **Tracks:** {amount}"
+        ))
+    }
+
+    async fn execute_load(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let name = self.arg_load_name(command);
+        let saved_playlist = playlist::get_playlist_store(ctx)
+            .await
+            .get(guild_id, command.user.id, &name)
+            .ok_or_else(|| Self::not_found(&name))?;
+        let (_, call, _) = utils::voice::join(ctx, command).await?;
+        let tracks = saved_playlist
+            .tracks
+            .into_iter()
+            .map(|track| QueuedTrack {
+                requester: command.user.id,
+                playlist: None,
+                verified: false,
+                ..track
+            })
+            .collect();
+        let summary = utils::voice::add_songs(
+            ctx,
+            call,
+            guild_id,
+            tracks,
+            usize::try_from(self.playlist_song_limit).unwrap_or_default(),
+            None,
+        )
+        .await;
+        Ok(format!(
+            "📂 **Playlist:** `{}` by {}\n\n{}\n👤 **Requested by:** {}",
+            saved_playlist.name,
+            saved_playlist.owner.mention(),
+            summary.describe(),
+            command.user.id.mention()
+        ))
+    }
+
+    async fn execute_add(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let name = self.arg_add_name(command);
+        let track = match self.arg_add_query(command) {
+            Some(query) => {
+                let is_url = Url::parse(&query).is_ok();
+                let metadata = utils::voice::fetch_metadata(ctx, query, is_url)
+                    .await
+                    .map_err(|err| {
+                        error!("❌ Failed to look up track: {err}");
+                        CadencyError::Command {
+                            message: "❌ **Couldn't find the track**".to_string(),
+                        }
+                    })?;
+                QueuedTrack {
+                    title: metadata.title,
+                    url: metadata.source_url,
+                    requester: command.user.id,
+                    playlist: None,
+                    duration: metadata.duration,
+                    verified: true,
+                }
+            }
+            None => {
+                let manager = utils::voice::get_songbird(ctx).await;
+                let current_track = match manager.get(guild_id) {
+                    Some(call) => call.lock().await.queue().current(),
+                    None => None,
+                };
+                let current_track = current_track.ok_or(CadencyError::Command {
+                    message: "❌ **No song is playing**".to_string(),
+                })?;
+                QueuedTrack::from(current_track.data::<TrackData>().as_ref())
+            }
+        };
+        if track.is_expiring() {
+            return Err(CadencyError::Command {
+                message: "❌ **Uploaded files can't be saved in playlists**".to_string(),
+            });
+        }
+        let title = track.title.clone().unwrap_or("Unknown Title".to_string());
+
+        let store = playlist::get_playlist_store(ctx).await;
+        let added = store
+            .update(guild_id, command.user.id, &name, |saved_playlist| {
+                if saved_playlist.tracks.len() < MAX_PLAYLIST_TRACKS {
+                    saved_playlist.tracks.push(track.clone());
+                    true
+                } else {
+                    false
+                }
+            })
+            .await?;
+        match added {
+            Some(false) => {
+                return Err(CadencyError::Command {
+                    message: format!(
+                        "❌ **A playlist can't have more than {MAX_PLAYLIST_TRACKS} tracks**"
+                    ),
+                })
+            }
+            Some(true) => {}
+            None => {
+                store
+                    .save(SavedPlaylist {
+                        name: name.clone(),
+                        owner: command.user.id,
+                        guild_id,
+                        shared: false,
+                        tracks: vec![track],
+                    })
+                    .await?
+            }
+        }
+        Ok(format!("✅ **Added to `{name}`:** {title}"))
+    }
+
+    async fn execute_remove(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let name = self.arg_remove_name(command);
+        let store = playlist::get_playlist_store(ctx).await;
+        match self.arg_remove_position(command) {
+            Some(position) => {
+                let removed = store
+                    .update(guild_id, command.user.id, &name, |saved_playlist| {
+                        usize::try_from(position)
+                            .ok()
+                            .and_then(|position| position.checked_sub(1))
+                            .filter(|index| *index < saved_playlist.tracks.len())
+                            .map(|index| saved_playlist.tracks.remove(index))
+                    })
+                    .await?
+                    .ok_or_else(|| Self::not_found(&name))?
+                    .ok_or(CadencyError::Command {
+                        message: "❌ **There is no track at this position**".to_string(),
+                    })?;
+                Ok(format!(
+                    "✅ **Removed from `{name}`:** {}",
+                    removed.title.as_deref().unwrap_or("Unknown Title")
+                ))
+            }
+            None => {
+                store
+                    .remove(guild_id, command.user.id, &name)
+                    .await?
+                    .ok_or_else(|| Self::not_found(&name))?;
+                Ok(format!("✅ **Removed playlist `{name}`**"))
+            }
+        }
+    }
+
+    async fn execute_list(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let playlists = playlist::get_playlist_store(ctx)
+            .await
+            .list(guild_id, command.user.id);
+        if playlists.is_empty() {
+            return Ok(
+                "📭 **No playlists yet**\n\nUse `/playlist save` to save the current queue."
+                    .to_string(),
+            );
+        }
+        let mut description = String::new();
+        for (index, saved_playlist) in playlists.iter().enumerate() {
+            let name: String = saved_playlist.name.chars().take(MAX_NAME_LENGTH).collect();
+            let line = format!(
+                "**{name}** • {} tracks • {}{}\n",
+                saved_playlist.tracks.len(),
+                saved_playlist.owner.mention(),
+                if saved_playlist.shared {
+                    " • 🌐 shared"
+                } else {
+                    ""
+                }
+            );
+            let more = format!("➕ **{} more** playlists", playlists.len() - index);
+            if description.chars().count() + line.chars().count() + more.chars().count()
+                > MAX_DESCRIPTION_LENGTH
+            {
+                description.push_str(&more);
+                break;
+            }
+            description.push_str(&line);
+        }
+        Ok(description)
+    }
+
+    async fn execute_share(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<String, CadencyError> {
+        let name = self.arg_share_name(command);
+        let shared = self.arg_share_shared(command);
+        playlist::get_playlist_store(ctx)
+            .await
+            .update(guild_id, command.user.id, &name, |saved_playlist| {
+                saved_playlist.shared = shared
+            })
+            .await?
+            .ok_or_else(|| Self::not_found(&name))?;
+        Ok(if shared {
+            format!("✅ **Playlist `{name}` is shared**\n\nEveryone on the server can load it.")
+        } else {
+            format!("✅ **Playlist `{name}` is private**\n\nOnly you can load it.")
+        })
+    }
+}
+
+#[async_trait]
+impl CadencyCommand for Playlist {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+
+        let description = if self.arg_save(command).is_some() {
+            self.execute_save(ctx, command, guild_id).await?
+        } else if self.arg_load(command).is_some() {
+            self.execute_load(ctx, command, guild_id).await?
+        } else if self.arg_add(command).is_some() {
+            self.execute_add(ctx, command, guild_id).await?
+        } else if self.arg_remove(command).is_some() {
+            self.execute_remove(ctx, command, guild_id).await?
+        } else if self.arg_list(command).is_some() {
+            self.execute_list(ctx, command, guild_id).await?
+        } else if self.arg_share(command).is_some() {
+            self.execute_share(ctx, command, guild_id).await?
+        } else {
+            return Err(CadencyError::Command {
+                message: "❌ **Unknown playlist command**".to_string(),
+            });
+        };
+
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("📂 Playlists")
+            .color(Colour::from_rgb(0, 255, 127)) // Spring green
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
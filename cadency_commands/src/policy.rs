@@ -0,0 +1,176 @@
+use cadency_core::{
+    guild,
+    response::{Response, ResponseBuilder},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{
+    all::Mentionable, async_trait, client::Context, model::application::CommandInteraction,
+};
+use std::time::Duration;
+
+#[derive(CommandBaseline, Default)]
+#[description = "Show or change who can control the music"]
+#[argument(
+    name = "dj_role",
+    description = "Role that is required to stop, skip, pause or loop tracks",
+    required = false,
+    kind = "Role"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "clear_dj_role",
+    description = "Allow everyone to stop, skip, pause or loop tracks",
+    required = false,
+    kind = "Boolean"
+)]
+#[argument(
+    name = "same_channel",
+    description = "Only users in the voice channel of the bot can control the music",
+    required = false,
+    kind = "Boolean"
+)]
+#[argument(
+    name = "vote_skip",
+    description = "Percentage of listeners that have to vote to skip (0 to skip at once)",
+    required = false,
+    kind = "Integer"
+)]
+#[argument(
+    name = "song_length",
+    description = "Maximum song length in minutes for non-DJs (0 for no limit)",
+    required = false,
+    kind = "Integer"
+)]
+#[argument(
+    name = "queue_length",
+    description = "Maximum queue length in minutes for non-DJs (0 for no limit)",
+    required = false,
+    kind = "Integer"
+)]
+pub struct Policy {}
+
+/// Read a limit in minutes, zero disables the limit
+fn minutes_limit(minutes: Option<i64>) -> Result<Option<Option<Duration>>, CadencyError> {
+    match minutes {
+        Some(minutes) if minutes < 0 => Err(CadencyError::Command {
+            message: "❌ **A limit can't be negative**".to_string(),
+        }),
+        Some(0) => Ok(Some(None)),
+        Some(minutes) => Ok(Some(Some(Duration::from_secs(minutes as u64 * 60)))),
+        None => Ok(None),
+    }
+}
+
+fn describe_limit(limit: Option<Duration>) -> String {
+    match limit {
+        Some(limit) => format!("{} minutes", limit.as_secs() / 60),
+        None => "None".to_string(),
+    }
+}
+
+#[async_trait]
+impl CadencyCommand for Policy {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let dj_role = match (self.arg_dj_role(command), self.arg_clear_dj_role(command)) {
+            (_, Some(true)) => Some(None),
+            (Some(role), _) => Some(Some(role)),
+            _ => None,
+        };
+        let same_channel = self.arg_same_channel(command);
+        let vote_skip = match self.arg_vote_skip(command) {
+            Some(percentage) if !(0..=100).contains(&percentage) => {
+                return Err(CadencyError::Command {
+                    message: "❌ **The vote skip percentage must be between 0 and 100**"
+                        .to_string(),
+                })
+            }
+            Some(0) => Some(None),
+            Some(percentage) => Some(Some(percentage as f32 / 100.0)),
+            None => None,
+        };
+        let song_length = minutes_limit(self.arg_song_length(command))?;
+        let queue_length = minutes_limit(self.arg_queue_length(command))?;
+        let is_change = dj_role.is_some()
+            || same_channel.is_some()
+            || vote_skip.is_some()
+            || song_length.is_some()
+            || queue_length.is_some();
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.manage_guild());
+        if is_change && !can_manage {
+            return Err(CadencyError::Command {
+                message: "❌ **You need the 'Manage Server' permission to change the policy**"
+                    .to_string(),
+            });
+        }
+
+        let settings = guild::get_guild_states(ctx)
+            .await
+            .update(guild_id, |state| {
+                if let Some(dj_role) = dj_role {
+                    state.settings.dj_role = dj_role;
+                }
+                if let Some(same_channel) = same_channel {
+                    state.settings.same_channel_only = same_channel;
+                }
+                if let Some(vote_skip) = vote_skip {
+                    state.settings.vote_skip_threshold = vote_skip;
+                }
+                if let Some(song_length) = song_length {
+                    state.settings.song_length_limit = song_length;
+                }
+                if let Some(queue_length) = queue_length {
+                    state.settings.max_queue_duration = queue_length;
+                }
+                state.settings.clone()
+            });
+
+        let mut description = if is_change {
+            "✅ **Music policy updated**\n\n".to_string()
+        } else {
+            String::new()
+        };
+        match settings.dj_role {
+            Some(role) => description.push_str(&format!("🎧 **DJ role:** {}", role.mention())),
+            None => description.push_str("🎧 **DJ role:** None"),
+        }
+        description.push_str(if settings.same_channel_only {
+            "\n🔊 **Control:** Only listeners in the voice channel"
+        } else {
+            "\n🔊 **Control:** Everyone on the server"
+        });
+        match settings.vote_skip_threshold {
+            Some(threshold) => description.push_str(&format!(
+                "\n🗳️ **Vote skip:** {:.0}% of the listeners",
+                threshold * 100.0
+            )),
+            None => description.push_str("\n🗳️ **Vote skip:** Disabled"),
+        }
+        description.push_str(&format!(
+            "\n⏱️ **Song length limit:** {}",
+            describe_limit(settings.song_length_limit)
+        ));
+        description.push_str(&format!(
+            "\n📜 **Queue length limit:** {}",
+            describe_limit(settings.max_queue_duration)
+        ));
+
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("🛡️ Music Policy")
+            .color(Colour::from_rgb(46, 139, 87)) // Sea green
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
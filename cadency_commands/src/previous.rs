@@ -0,0 +1,110 @@
+use cadency_core::{
+    guild,
+    response::{Response, ResponseBuilder},
+    track::TrackData,
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{async_trait, client::Context, model::application::CommandInteraction};
+
+#[derive(CommandBaseline, Default)]
+#[description = "Play the previous track next"]
+#[deferred = true]
+pub struct Previous {}
+
+#[async_trait]
+impl CadencyCommand for Previous {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        // The track is moved in front of the tracks of everyone else
+        utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+        let guild_states = guild::get_guild_states(ctx).await;
+        let previous_track = guild_states
+            .get(guild_id, |state| state.history.back().cloned())
+            .ok_or(CadencyError::Command {
+                message: "❌ **There is no previous track**".to_string(),
+            })?;
+
+        let (_, call, _) = utils::voice::join(ctx, command).await?;
+        let (payload, is_url) = match (&previous_track.url, &previous_track.title) {
+            (Some(url), _) => (url.clone(), true),
+            (None, Some(title)) => (title.clone(), false),
+            (None, None) => {
+                return Err(CadencyError::Command {
+                    message: "❌ **The previous track can't be found again**".to_string(),
+                })
+            }
+        };
+        let (added_song_meta, track_handle) = utils::voice::add_song(
+            ctx,
+            call.clone(),
+            guild_id,
+            payload,
+            is_url,
+            command.user.id,
+            None,
+        )
+        .await
+        .map_err(|err| {
+            error!("❌ Failed to add previous song to queue: {}", err);
+            CadencyError::Command {
+                message: "❌ **Couldn't add the previous track to the queue!**".to_string(),
+            }
+        })?;
+
+        // The track is only taken from the history once it is back in the queue
+        track_handle.data::<TrackData>().mark_replayed();
+        guild_states.update(guild_id, |state| {
+            let position = state.history.iter().rposition(|played| {
+                played.finished_at == previous_track.finished_at && played.url == previous_track.url
+            });
+            if let Some(position) = position {
+                state.history.remove(position);
+            }
+        });
+
+        // The fair order of the queue decides when the track plays, otherwise it plays next
+        let fair_queue = guild_states.get(guild_id, |state| state.settings.fair_queue);
+        if !fair_queue {
+            let handler = call.lock().await;
+            // Other tracks may have been added in the meantime, so the track is found by its id
+            handler.queue().modify_queue(|queue| {
+                let position = queue
+                    .iter()
+                    .position(|track| track.uuid() == track_handle.uuid());
+                if let Some(track) = position
+                    .filter(|position| *position > 1)
+                    .and_then(|position| queue.remove(position))
+                {
+                    queue.insert(1, track);
+                }
+            });
+        }
+
+        let title = added_song_meta
+            .title
+            .as_ref()
+            .map_or("Unknown Title", |title| title);
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("⏮️ Previous")
+            .color(Colour::from_rgb(65, 105, 225)) // Royal blue
+            .description(format!(
+                "🎵 **Title:** `{}`\n\n✅ **Added to {}!**",
+                title,
+                if fair_queue {
+                    "the queue"
+                } else {
+                    "the front of the queue"
+                }
+            ));
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
@@ -0,0 +1,246 @@
+use crate::play;
+use cadency_core::{
+    export::{self, ExportedTrack},
+    guild,
+    response::{Response, ResponseBuilder},
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{
+    async_trait,
+    builder::{CreateAttachment, CreateEmbed},
+    client::Context,
+    model::application::CommandInteraction,
+};
+
+/// Maximum size of an imported queue file in bytes
+const MAX_IMPORT_SIZE: u32 = 1024 * 1024;
+
+#[derive(CommandBaseline)]
+#[description = "Manage the queue of the server"]
+#[deferred = true]
+#[argument(
+    name = "mode",
+    description = "Show or change how tracks are queued",
+    kind = "SubCommand"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "fair",
+    description = "Take turns between the tracks of different users",
+    required = false,
+    kind = "Boolean",
+    subcommand = "mode"
+)]
+#[argument(
+    name = "user_limit",
+    description = "Maximum amount of queued tracks per user (0 for no limit)",
+    required = false,
+    kind = "Integer",
+    subcommand = "mode"
+)]
+#[argument(
+    name = "export",
+    description = "Download the queue as M3U8 and json file",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "import",
+    description = "Add the tracks of an exported queue",
+    kind = "SubCommand"
+)]
+#[argument(
+    name = "file",
+    description = "M3U8 or json file of an exported queue",
+    kind = "Attachment",
+    subcommand = "import"
+)]
+pub struct Queue {
+    /// The maximum number of songs that can be added to the queue from an import
+    playlist_song_limit: i32,
+}
+
+impl Queue {
+    pub fn new(playlist_song_limit: i32) -> Self {
+        Self {
+            playlist_song_limit,
+        }
+    }
+
+    async fn execute_export(
+        &self,
+        ctx: &Context,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<(String, Vec<CreateAttachment>), CadencyError> {
+        let manager = utils::voice::get_songbird(ctx).await;
+        let call = manager.get(guild_id).ok_or(CadencyError::Command {
+            message: "❌ **No active voice session on the server**".to_string(),
+        })?;
+        let snapshot = {
+            let handler = call.lock().await;
+            utils::queue::snapshot_queue(handler.queue()).await
+        };
+        if snapshot.tracks.is_empty() {
+            return Err(CadencyError::Command {
+                message: "❌ **There are no tracks in the queue to export**".to_string(),
+            });
+        }
+        let tracks: Vec<ExportedTrack> = snapshot.tracks.iter().map(ExportedTrack::from).collect();
+        let attachments = vec![
+            CreateAttachment::bytes(export::to_m3u8(&tracks), "queue.m3u8"),
+            CreateAttachment::bytes(export::to_json(&tracks)?, "queue.json"),
+        ];
+        Ok((
+            format!(
+                "✅ **Exported {} track{}**\n\nUse `/queue import` to add them to a queue again.",
+                tracks.len(),
+                if tracks.len() == 1 { "" } else { "s" }
+            ),
+            attachments,
+        ))
+    }
+
+    async fn execute_import(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        guild_id: serenity::model::id::GuildId,
+    ) -> Result<CreateEmbed, CadencyError> {
+        let attachment_id = self.arg_import_file(command);
+        let attachment = command
+            .data
+            .resolved
+            .attachments
+            .get(&attachment_id)
+            .ok_or(CadencyError::Command {
+                message: "❌ **The file could not be found**".to_string(),
+            })?;
+        if attachment.size > MAX_IMPORT_SIZE {
+            return Err(CadencyError::Command {
+                message: "❌ **The file is too large**".to_string(),
+            });
+        }
+        let content = attachment.download().await.map_err(|err| {
+            error!("❌ Failed to download queue file: {err}");
+            CadencyError::Command {
+                message: "❌ **The file could not be downloaded**".to_string(),
+            }
+        })?;
+        let tracks = String::from_utf8(content)
+            .ok()
+            .and_then(|content| export::parse(&content).ok())
+            .ok_or(CadencyError::Command {
+                message: "❌ **The file is no exported queue**".to_string(),
+            })?;
+
+        let (_, call, _) = utils::voice::join(ctx, command).await?;
+        let tracks = tracks
+            .into_iter()
+            .map(|track| track.into_queued_track(command.user.id))
+            .collect();
+        // Large imports are added in the background like the playlists of /play
+        Ok(play::enqueue_tracks(
+            ctx,
+            command,
+            call,
+            guild_id,
+            tracks,
+            self.playlist_song_limit,
+        )
+        .await)
+    }
+
+    async fn execute_mode(
+        &self,
+        ctx: &Context,
+        guild_states: &guild::GuildStates,
+        guild_id: serenity::model::id::GuildId,
+        command: &CommandInteraction,
+    ) -> Result<String, CadencyError> {
+        let user_limit = match self.arg_mode_user_limit(command) {
+            Some(limit) if limit < 0 => {
+                return Err(CadencyError::Command {
+                    message: "❌ **The user limit can't be negative**".to_string(),
+                })
+            }
+            Some(0) => Some(None),
+            Some(limit) => Some(Some(limit as usize)),
+            None => None,
+        };
+        let fair = self.arg_mode_fair(command);
+        // Everyone can see the mode, changing it affects all users of the server
+        if fair.is_some() || user_limit.is_some() {
+            utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+        }
+
+        let (fair_queue, user_queue_limit) = guild_states.update(guild_id, |state| {
+            if let Some(fair) = fair {
+                state.settings.fair_queue = fair;
+            }
+            if let Some(user_limit) = user_limit {
+                state.settings.user_queue_limit = user_limit;
+            }
+            (state.settings.fair_queue, state.settings.user_queue_limit)
+        });
+
+        let mut description = if fair.is_some() || user_limit.is_some() {
+            "✅ **Queue mode updated**\n\n".to_string()
+        } else {
+            String::new()
+        };
+        description.push_str(if fair_queue {
+            "⚖️ **Mode:** Fair (users take turns)"
+        } else {
+            "➡️ **Mode:** In order of request"
+        });
+        match user_queue_limit {
+            Some(limit) => {
+                description.push_str(&format!("\n👤 **Limit per user:** {limit} tracks"));
+            }
+            None => description.push_str("\n👤 **Limit per user:** None"),
+        }
+        Ok(description)
+    }
+}
+
+#[async_trait]
+impl CadencyCommand for Queue {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let guild_states = guild::get_guild_states(ctx).await;
+
+        let mut attachments = Vec::new();
+        let description = if self.arg_mode(command).is_some() {
+            self.execute_mode(ctx, &guild_states, guild_id, command)
+                .await?
+        } else if self.arg_export(command).is_some() {
+            let (description, files) = self.execute_export(ctx, guild_id).await?;
+            attachments = files;
+            description
+        } else if self.arg_import(command).is_some() {
+            let embed = self.execute_import(ctx, command, guild_id).await?;
+            return Ok(response_builder.embeds(vec![embed]).build()?);
+        } else {
+            return Err(CadencyError::Command {
+                message: "❌ **Unknown queue command**".to_string(),
+            });
+        };
+
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("📋 Queue")
+            .color(Colour::from_rgb(114, 137, 218)) // Discord blurple
+            .description(description);
+        Ok(response_builder
+            .embeds(vec![embed])
+            .attachments(attachments)
+            .build()?)
+    }
+}
@@ -0,0 +1,79 @@
+use cadency_core::{
+    response::{Response, ResponseBuilder},
+    utils, CadencyCommand, CadencyError,
+};
+use reqwest::Url;
+use serenity::model::colour::Colour;
+use serenity::{
+    all::Mentionable, async_trait, client::Context, model::application::CommandInteraction,
+};
+
+#[derive(CommandBaseline, Default)]
+#[description = "Play an internet radio or live stream"]
+#[deferred = true]
+#[argument(
+    name = "url",
+    description = "URL of an Icecast, Shoutcast or HLS stream",
+    kind = "String"
+)]
+pub struct Radio {}
+
+#[async_trait]
+impl CadencyCommand for Radio {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let url = self.arg_url(command);
+        let direct_audio = if Url::parse(&url).is_ok() {
+            utils::voice::detect_direct_audio(ctx, &url).await
+        } else {
+            None
+        };
+        if !direct_audio
+            .as_ref()
+            .is_some_and(|direct_audio| direct_audio.is_live())
+        {
+            return Err(CadencyError::Command {
+                message:
+                    "❌ **This url is no radio stream**\n\nPlease use `/play` for songs and videos."
+                        .to_string(),
+            });
+        }
+
+        let (_, call, guild_id) = utils::voice::join(ctx, command).await?;
+        let (stream_meta, _) = utils::voice::add_detected_song(
+            ctx,
+            call,
+            guild_id,
+            url.clone(),
+            direct_audio,
+            command.user.id,
+            None,
+        )
+        .await
+        .map_err(|err| {
+            error!("❌ Failed to add radio stream to queue: {err}");
+            let message = if let CadencyError::UserQueueLimit { limit } = err {
+                format!("❌ **Queue limit reached!**\n\nYou can only have {limit} tracks in the queue at the same time.")
+            } else {
+                "❌ **Couldn't tune in to the radio stream!**".to_string()
+            };
+            CadencyError::Command { message }
+        })?;
+
+        let description = format!(
+            "📻 **Station:** `{}`\n🔗 **Stream:** [Open Link]({})\n🔴 **Live**\n👤 **Requested by:** {}\n\n✅ **Added to queue!**",
+            stream_meta.title.as_deref().unwrap_or("Unknown Station"),
+            url,
+            command.user.id.mention()
+        );
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("📻 Radio Added")
+            .color(Colour::from_rgb(220, 20, 60)) // Crimson
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
@@ -0,0 +1,244 @@
+use cadency_core::{
+    response::{Response, ResponseBuilder},
+    utils, CadencyCommand, CadencyError,
+};
+use cadency_yt_playlist::SearchItem;
+use serenity::model::colour::Colour;
+use serenity::{
+    all::{ComponentInteractionDataKind, Mentionable},
+    async_trait,
+    builder::{
+        CreateActionRow, CreateInteractionResponse, CreateSelectMenu, CreateSelectMenuKind,
+        CreateSelectMenuOption, EditInteractionResponse,
+    },
+    client::Context,
+    model::application::CommandInteraction,
+};
+use std::time::Duration;
+
+/// Amount of search results if not specified
+const DEFAULT_RESULTS: i64 = 5;
+/// Discord allows up to 25 options in a select menu
+const MAX_RESULTS: i64 = 25;
+/// How long the user can take to pick a result
+const PICK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Discord allows up to 100 characters for labels and descriptions of options
+const MAX_OPTION_LENGTH: usize = 100;
+/// Discord allows up to 4096 characters in embed descriptions
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+
+#[derive(CommandBaseline, Default)]
+#[description = "Search YouTube and pick the song to play"]
+#[deferred = true]
+#[argument(
+    name = "query",
+    description = "Search query like: 'Hey Jude Beatles'",
+    kind = "String"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "results",
+    description = "Amount of results to pick from, up to 25",
+    required = false,
+    kind = "Integer"
+)]
+pub struct Search {}
+
+/// Results without duration are live streams
+fn result_duration(duration: Option<f32>) -> String {
+    match duration.and_then(|duration| Duration::try_from_secs_f32(duration.max(0.0)).ok()) {
+        Some(duration) => utils::voice::format_duration(duration),
+        None => "Live".to_string(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_OPTION_LENGTH).collect()
+}
+
+/// Brackets in titles would end the markdown link early
+fn escape_link_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '[' | ']' | '(' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+/// List the results, the ones that don't fit into the embed can still be picked in the menu
+fn results_description(results: &[SearchItem]) -> String {
+    let mut description = String::new();
+    for (index, item) in results.iter().enumerate() {
+        let line = format!(
+            "**{}.** [{}]({})\n⏱️ {} • 📺 {}\n",
+            index + 1,
+            escape_link_text(&truncate(&item.title)),
+            item.url,
+            result_duration(item.duration),
+            truncate(item.channel_name().unwrap_or("Unknown Channel"))
+        );
+        let more = format!("➕ **{} more** in the menu", results.len() - index);
+        if description.chars().count() + line.chars().count() + more.chars().count()
+            > MAX_DESCRIPTION_LENGTH
+        {
+            description.push_str(&more);
+            break;
+        }
+        description.push_str(&line);
+    }
+    description
+}
+
+fn select_option(index: usize, item: &SearchItem) -> CreateSelectMenuOption {
+    let description = format!(
+        "{} • {}",
+        result_duration(item.duration),
+        item.channel_name().unwrap_or("Unknown Channel")
+    );
+    CreateSelectMenuOption::new(
+        truncate(&format!("{}. {}", index + 1, item.title)),
+        item.url.clone(),
+    )
+    .description(truncate(&description))
+}
+
+#[async_trait]
+impl CadencyCommand for Search {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let query = self.arg_query(command);
+        let amount = self
+            .arg_results(command)
+            .unwrap_or(DEFAULT_RESULTS)
+            .clamp(1, MAX_RESULTS) as usize;
+
+        // yt-dlp blocks until the search is done
+        let search_query = query.clone();
+        let results = tokio::task::spawn_blocking(move || {
+            cadency_yt_playlist::search_songs(search_query, amount)
+        })
+        .await
+        .map_err(|err| CadencyError::Runtime(format!("Search task failed: {err}")))?
+        .map_err(|err| {
+            error!("❌ Failed to search for '{query}': {err}");
+            CadencyError::Command {
+                message: "❌ **The search failed**".to_string(),
+            }
+        })?
+        .data;
+        if results.is_empty() {
+            return Err(CadencyError::Command {
+                message: format!("❌ **No results for '{query}'**"),
+            });
+        }
+
+        let description = results_description(&results);
+        let embed = serenity::builder::CreateEmbed::default()
+            .title(format!("🔍 Results for '{}'", truncate(&query)))
+            .color(Colour::from_rgb(255, 215, 0)) // Gold
+            .description(description);
+        let select_menu = CreateSelectMenu::new(
+            "search_pick",
+            CreateSelectMenuKind::String {
+                options: results
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| select_option(index, item))
+                    .collect(),
+            },
+        )
+        .placeholder("Pick a song to play");
+        let message = command
+            .edit_response(
+                &ctx.http,
+                EditInteractionResponse::new()
+                    .embed(embed)
+                    .components(vec![CreateActionRow::SelectMenu(select_menu)]),
+            )
+            .await
+            .map_err(|err| {
+                error!("Failed to show search results: {err}");
+                CadencyError::Response
+            })?;
+
+        // Only the user that searched can pick a result
+        let Some(pick) = message
+            .await_component_interaction(&ctx.shard)
+            .author_id(command.user.id)
+            .timeout(PICK_TIMEOUT)
+            .await
+        else {
+            let embed = serenity::builder::CreateEmbed::default()
+                .title("⌛ Search Expired")
+                .color(Colour::from_rgb(128, 128, 128)) // Gray
+                .description("No song was picked, use `/search` again.");
+            return Ok(response_builder.embeds(vec![embed]).build()?);
+        };
+        if let Err(err) = pick
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await
+        {
+            error!("Failed to acknowledge search pick: {err}");
+        }
+        let ComponentInteractionDataKind::StringSelect { values } = &pick.data.kind else {
+            return Err(CadencyError::Command {
+                message: "❌ **Unknown search result**".to_string(),
+            });
+        };
+        let url = values.first().cloned().ok_or(CadencyError::Command {
+            message: "❌ **No song was picked**".to_string(),
+        })?;
+
+        let (_, call, guild_id) = utils::voice::join(ctx, command).await?;
+        let added = utils::voice::add_song(
+            ctx,
+            call,
+            guild_id,
+            url.clone(),
+            true,
+            command.user.id,
+            None,
+        )
+        .await;
+        let (added_song_meta, _) = match added {
+            Ok(added) => added,
+            Err(err) => {
+                error!("❌ Failed to add song to queue: {err}");
+                if let Some(embed) = utils::voice::rejection_embed(&err) {
+                    return Ok(response_builder.embeds(vec![embed]).build()?);
+                }
+                return Err(CadencyError::Command {
+                    message: "❌ **Couldn't add audio source to the queue!**".to_string(),
+                });
+            }
+        };
+
+        let mut description = format!(
+            "🎵 **Title:** `{}`\n🔗 **Source:** [View on YouTube]({})",
+            added_song_meta.title.as_deref().unwrap_or("Unknown Title"),
+            url
+        );
+        if let Some(duration) = added_song_meta.duration {
+            description.push_str(&format!(
+                "\n⏱️ **Duration:** {}",
+                utils::voice::format_duration(duration)
+            ));
+        }
+        description.push_str(&format!(
+            "\n👤 **Requested by:** {}\n\n✅ **Added to queue!**",
+            command.user.id.mention()
+        ));
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("🎶 Song Added")
+            .color(Colour::from_rgb(65, 105, 225)) // Royal blue
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
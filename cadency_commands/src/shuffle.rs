@@ -0,0 +1,25 @@
+use cadency_core::{
+    response::{Response, ResponseBuilder},
+    utils::{controls, policy::Controller},
+    CadencyCommand, CadencyError,
+};
+use serenity::{async_trait, client::Context, model::application::CommandInteraction};
+
+#[derive(CommandBaseline, Default)]
+#[description = "Shuffle the upcoming songs in the queue"]
+#[deferred = true]
+pub struct Shuffle {}
+
+#[async_trait]
+impl CadencyCommand for Shuffle {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let controller = Controller::from_command(command)?;
+        let embed = controls::shuffle(ctx, &controller).await?;
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
@@ -0,0 +1,80 @@
+use cadency_core::{
+    guild,
+    response::{Response, ResponseBuilder},
+    utils::{self, policy::MusicAction},
+    CadencyCommand, CadencyError,
+};
+use serenity::model::colour::Colour;
+use serenity::{async_trait, client::Context, model::application::CommandInteraction};
+use std::time::Duration;
+
+#[derive(CommandBaseline, Default)]
+#[description = "Toggle 24/7 mode or change when the bot leaves the voice channel"]
+#[argument(
+    name = "enabled",
+    description = "Stay in the voice channel even when nothing is playing",
+    required = false,
+    kind = "Boolean"
+)]
+#[allow(clippy::duplicated_attributes)]
+#[argument(
+    name = "timeout",
+    description = "Seconds without tracks before the bot leaves",
+    required = false,
+    kind = "Integer"
+)]
+pub struct Stay {}
+
+#[async_trait]
+impl CadencyCommand for Stay {
+    async fn execute<'a>(
+        &self,
+        ctx: &Context,
+        command: &'a mut CommandInteraction,
+        response_builder: &'a mut ResponseBuilder,
+    ) -> Result<Response, CadencyError> {
+        let guild_id = command.guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        let timeout = match self.arg_timeout(command) {
+            Some(seconds) if seconds < 0 => {
+                return Err(CadencyError::Command {
+                    message: "❌ **The timeout can't be negative**".to_string(),
+                })
+            }
+            Some(seconds) => Some(Duration::from_secs(seconds as u64)),
+            None => None,
+        };
+        let enabled = self.arg_enabled(command);
+        utils::policy::check_music_policy(ctx, command, MusicAction::Destructive).await?;
+        let guild_states = guild::get_guild_states(ctx).await;
+
+        let (stay, inactive_timeout) = guild_states.update(guild_id, |state| {
+            // Without arguments the 24/7 mode is toggled
+            state.settings.stay = match (enabled, timeout) {
+                (Some(enabled), _) => enabled,
+                (None, Some(_)) => state.settings.stay,
+                (None, None) => !state.settings.stay,
+            };
+            if let Some(timeout) = timeout {
+                state.settings.inactive_timeout = timeout;
+            }
+            (state.settings.stay, state.settings.inactive_timeout)
+        });
+
+        let description = if stay {
+            "✅ **24/7 mode enabled**\n\nThe bot stays in the voice channel even when nothing is playing."
+                .to_string()
+        } else {
+            format!(
+                "✅ **24/7 mode disabled**\n\nThe bot leaves the voice channel after **{}** seconds without tracks.",
+                inactive_timeout.as_secs()
+            )
+        };
+        let embed = serenity::builder::CreateEmbed::default()
+            .title("🌙 Stay")
+            .color(Colour::from_rgb(72, 61, 139)) // Dark slate blue
+            .description(description);
+        Ok(response_builder.embeds(vec![embed]).build()?)
+    }
+}
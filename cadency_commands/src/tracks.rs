@@ -1,18 +1,160 @@
 use cadency_core::{
     response::{Response, ResponseBuilder},
-    utils, CadencyCommand, CadencyError,
+    track::TrackData,
+    utils::{self, voice::format_duration},
+    CadencyCommand, CadencyError,
 };
 use serenity::{
-    async_trait, builder::CreateEmbed, client::Context, model::application::CommandInteraction,
-    model::colour::Colour,
+    all::Mentionable,
+    async_trait,
+    builder::{CreateEmbed, CreateEmbedFooter},
+    client::Context,
+    model::{application::CommandInteraction, colour::Colour, id::UserId},
 };
-use songbird::{input::AuxMetadata, tracks::LoopState};
+use songbird::tracks::LoopState;
+use std::time::Duration;
+
+/// Discord allows up to 25 fields per embed and 6000 characters in total
+const TRACKS_PER_PAGE: usize = 10;
+/// Discord allows up to 256 characters in field names
+const MAX_TITLE_LENGTH: usize = 200;
 
 #[derive(CommandBaseline, Default)]
 #[description = "List all tracks in the queue"]
 #[deferred = true]
 pub struct Tracks {}
 
+/// A queued track as it is shown in the list
+struct QueueEntry {
+    title: String,
+    url: Option<String>,
+    requester: UserId,
+    duration: Option<Duration>,
+    is_live: bool,
+    /// Unknown if the state of the track couldn't be fetched
+    loops: Option<LoopState>,
+    starts_in: Option<Duration>,
+}
+
+struct QueuePages {
+    entries: Vec<QueueEntry>,
+    /// Playing time that is left, without tracks of unknown length
+    remaining: Duration,
+    /// Playback position of the current track
+    position: Option<Duration>,
+}
+
+impl QueuePages {
+    fn estimate_start_times(&mut self) {
+        // A looping track never ends, so nothing after it has a start time
+        let current_remaining = self.entries.first().and_then(|current| {
+            let is_looping = matches!(
+                current.loops,
+                None | Some(LoopState::Infinite | LoopState::Finite(1..))
+            );
+            current
+                .duration
+                .filter(|_| !is_looping && !current.is_live)
+                .zip(self.position)
+                .map(|(duration, position)| duration.saturating_sub(position))
+        });
+        let durations: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| entry.duration.filter(|_| !entry.is_live))
+            .collect();
+        let start_times = utils::queue::estimated_start_times(current_remaining, &durations);
+        for (entry, starts_in) in self.entries.iter_mut().zip(start_times) {
+            entry.starts_in = starts_in;
+        }
+    }
+
+    fn page_count(&self) -> usize {
+        self.entries.len().div_ceil(TRACKS_PER_PAGE).max(1)
+    }
+
+    fn field(&self, index: usize, entry: &QueueEntry) -> (String, String) {
+        let title: String = entry.title.chars().take(MAX_TITLE_LENGTH).collect();
+        let name = if index == 0 {
+            format!("▶️ 1. {title}")
+        } else {
+            format!("{}. {title}", index + 1)
+        };
+
+        let mut value = match &entry.url {
+            Some(url) => format!("🔗 [View Source]({url})"),
+            None => "🔗 No URL available".to_string(),
+        };
+        value.push_str(&format!(" • 👤 {}", entry.requester.mention()));
+        match (entry.is_live, entry.duration) {
+            (true, _) => value.push_str(" • 🔴 Live"),
+            (false, Some(duration)) if index == 0 => value.push_str(&format!(
+                " • ⏱️ {} / {}",
+                format_duration(self.position.unwrap_or_default()),
+                format_duration(duration)
+            )),
+            (false, Some(duration)) => {
+                value.push_str(&format!(" • ⏱️ {}", format_duration(duration)))
+            }
+            (false, None) => {}
+        }
+        if index > 0 {
+            match entry.starts_in {
+                Some(starts_in) => value.push_str(&format!(
+                    "\n🕒 **Plays in:** {}",
+                    format_duration(starts_in)
+                )),
+                None => value.push_str("\n🕒 **Plays in:** unknown"),
+            }
+        }
+        match entry.loops {
+            Some(LoopState::Infinite) => value.push_str("\n🔁 **Loop:** Infinite"),
+            Some(LoopState::Finite(loop_amount)) if loop_amount > 0 => {
+                value.push_str(&format!("\n🔁 **Loop:** {} times", loop_amount))
+            }
+            Some(_) => {}
+            None => value.push_str("\n⚠️ Playback state unavailable"),
+        }
+        (name, value)
+    }
+
+    fn embed(&self, page: usize) -> CreateEmbed {
+        let has_unknown_length = self
+            .entries
+            .iter()
+            .any(|entry| entry.is_live || entry.duration.is_none());
+        let mut description = format!(
+            "📊 **Total Tracks:** {}\n⏱️ **Total Duration:** {}",
+            self.entries.len(),
+            format_duration(self.remaining)
+        );
+        if has_unknown_length {
+            description.push_str(" + tracks of unknown length");
+        }
+        let fields: Vec<_> = self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(page * TRACKS_PER_PAGE)
+            .take(TRACKS_PER_PAGE)
+            .map(|(index, entry)| {
+                let (name, value) = self.field(index, entry);
+                (name, value, false)
+            })
+            .collect();
+        CreateEmbed::default()
+            .color(Colour::from_rgb(114, 137, 218)) // Discord blurple
+            .title("🎵 Track Queue")
+            .description(description)
+            .fields(fields)
+            .footer(CreateEmbedFooter::new(format!(
+                "Page {}/{}",
+                page + 1,
+                self.page_count()
+            )))
+    }
+}
+
 #[async_trait]
 impl CadencyCommand for Tracks {
     async fn execute<'a>(
@@ -28,55 +170,63 @@ impl CadencyCommand for Tracks {
         let call = manager.get(guild_id).ok_or(CadencyError::Command {
             message: "❌ **No active voice session on the server**".to_string(),
         })?;
-        let handler = call.lock().await;
-        let response_builder = if handler.queue().is_empty() {
-            response_builder.message(Some(
-                "❌ **No tracks in the queue**\n\nUse `/play` to add some music!".to_string(),
-            ))
-        } else {
-            let queue_snapshot = handler.queue().current_queue();
-            let mut embeded_tracks = CreateEmbed::default()
-                .color(Colour::from_rgb(114, 137, 218)) // Discord blurple
-                .title("🎵 Track Queue")
-                .description(format!("📊 **Total Tracks:** {}", queue_snapshot.len()));
-
-            for (index, track) in queue_snapshot.into_iter().enumerate() {
-                let track_position = index + 1;
-                let (title, url, loop_state) = {
-                    let metadata = track.data::<AuxMetadata>();
-                    let title = metadata.title.as_ref().map_or("Unknown Title", |t| t);
-                    let url = metadata.source_url.as_ref().map_or("No URL", |u| u);
-                    let track_info = track.get_info().await.unwrap();
-                    (title.to_owned(), url.to_owned(), track_info.loops)
-                };
-
-                let mut embed_value = if url != "No URL" {
-                    format!("🔗 [View Source]({})", url)
-                } else {
-                    "🔗 No URL available".to_string()
-                };
 
-                match loop_state {
-                    LoopState::Infinite => {
-                        embed_value.push_str("\n🔁 **Loop:** Infinite");
-                    }
-                    LoopState::Finite(loop_amount) => {
-                        if loop_amount > 0 {
-                            embed_value.push_str(&format!("\n🔁 **Loop:** {} times", loop_amount));
-                        }
+        // The queue is only locked while the list is collected, not while the pages are turned
+        let mut pages = {
+            let handler = call.lock().await;
+            if handler.queue().is_empty() {
+                return Ok(response_builder
+                    .message(Some(
+                        "❌ **No tracks in the queue**\n\nUse `/play` to add some music!"
+                            .to_string(),
+                    ))
+                    .build()?);
+            }
+            let mut entries = Vec::new();
+            let mut position = None;
+            for (index, track) in handler.queue().current_queue().into_iter().enumerate() {
+                let track_data = track.data::<TrackData>();
+                let track_info = match track.get_info().await {
+                    Ok(track_info) => Some(track_info),
+                    Err(err) => {
+                        debug!("Unable to fetch state of a queued track: {err}");
+                        None
                     }
-                }
-
-                let field_name = if index == 0 {
-                    format!("▶️ {}. {}", track_position, title)
-                } else {
-                    format!("{}. {}", track_position, title)
                 };
-
-                embeded_tracks = embeded_tracks.field(field_name, embed_value, false);
+                if index == 0 {
+                    position = track_info.as_ref().map(|track_info| track_info.position);
+                }
+                entries.push(QueueEntry {
+                    title: track_data.title().unwrap_or("Unknown Title").to_owned(),
+                    // Library files have a local url that can't be opened in Discord
+                    url: track_data
+                        .url()
+                        .filter(|url| url.starts_with("http"))
+                        .map(str::to_owned),
+                    requester: track_data.requester,
+                    duration: track_data.metadata.duration,
+                    is_live: track_data.live.is_some(),
+                    loops: track_info.map(|track_info| track_info.loops),
+                    starts_in: None,
+                });
+            }
+            let remaining = utils::queue::remaining_duration(handler.queue()).await;
+            QueuePages {
+                entries,
+                remaining,
+                position,
             }
-            response_builder.embeds(vec![embeded_tracks])
         };
-        Ok(response_builder.build()?)
+        pages.estimate_start_times();
+        if pages.page_count() == 1 {
+            return Ok(response_builder.embeds(vec![pages.embed(0)]).build()?);
+        }
+
+        let page = utils::pages::turn_pages(ctx, command, "tracks", pages.page_count(), |page| {
+            pages.embed(page)
+        })
+        .await?;
+        // The buttons are removed once the pages can't be turned anymore
+        Ok(response_builder.embeds(vec![pages.embed(page)]).build()?)
     }
 }
@@ -1,15 +1,22 @@
 use crate::{
     command::{Commands, CommandsScope},
     error::CadencyError,
+    guild::{GuildSettings, GuildStates},
     handler::command::Handler,
     http::HttpClientKey,
     intents::CadencyIntents,
+    library::Library,
+    lyrics::{LrclibProvider, LyricsProvider, LyricsProviderKey},
+    music_link::{EmbedMetadataProvider, MusicLinkProvider, MusicLinkProviderKey},
+    persistence::{self, PendingQueues, QueueFile},
+    playlist::PlaylistStore,
     CadencyCommand,
 };
 use ctrlc;
-use log::info;
+use log::{error, info};
 use serenity::{client::Client, model::gateway::GatewayIntents};
 use songbird::SerenityInit;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 use tokio::sync::oneshot;
@@ -24,6 +31,24 @@ pub struct Cadency {
     /// Used when registering commands with the Discord API
     #[builder(default)]
     commands_scope: CommandsScope,
+    /// Music settings of every guild until they are changed by a command
+    #[builder(default)]
+    guild_settings: GuildSettings,
+    /// Queues are saved to this file on shutdown and resumed on the next start
+    #[builder(default)]
+    queue_file: Option<PathBuf>,
+    /// Saved playlists of the users are stored in this file, they are lost on shutdown if not set
+    #[builder(default)]
+    playlist_file: Option<PathBuf>,
+    /// Folder with audio files that can be played with the library command
+    #[builder(default)]
+    library_path: Option<PathBuf>,
+    /// Looks up the tracks of Spotify and Apple Music links, reads their public pages if not set
+    #[builder(default)]
+    music_link_provider: Option<Arc<dyn MusicLinkProvider>>,
+    /// Looks up the lyrics of songs, uses LRCLIB if not set
+    #[builder(default)]
+    lyrics_provider: Option<Arc<dyn LyricsProvider>>,
 }
 
 impl Cadency {
@@ -34,13 +59,42 @@ impl Cadency {
 
     /// This will actually start the configured Cadency bot
     pub async fn start(self) -> Result<(), CadencyError> {
+        let playlist_store = PlaylistStore::load(self.playlist_file)?;
+        let library = match self.library_path {
+            Some(library_path) => {
+                info!("📚 Index music library at {}", library_path.display());
+                // Reading the tags of many files takes a while and blocks the thread
+                let library = tokio::task::spawn_blocking(move || Library::index(&library_path))
+                    .await
+                    .map_err(|err| {
+                        CadencyError::Runtime(format!("Failed to index library: {err}"))
+                    })??;
+                info!("📚 Indexed {} tracks", library.len());
+                library
+            }
+            None => Library::default(),
+        };
+        let http_client = reqwest::Client::new();
+        let music_link_provider = self
+            .music_link_provider
+            .unwrap_or_else(|| Arc::new(EmbedMetadataProvider::new(http_client.clone())));
+        let lyrics_provider = self
+            .lyrics_provider
+            .unwrap_or_else(|| Arc::new(LrclibProvider::new(http_client.clone())));
         // Build the serenity client as before
         let mut client = Client::builder(self.token, self.intents)
             .event_handler(Handler)
             .register_songbird()
             .type_map_insert::<Commands>(self.commands)
-            .type_map_insert::<HttpClientKey>(reqwest::Client::new())
+            .type_map_insert::<HttpClientKey>(http_client)
             .type_map_insert::<CommandsScope>(self.commands_scope)
+            .type_map_insert::<GuildStates>(GuildStates::new(self.guild_settings))
+            .type_map_insert::<QueueFile>(self.queue_file.clone())
+            .type_map_insert::<PendingQueues>(Arc::default())
+            .type_map_insert::<PlaylistStore>(playlist_store)
+            .type_map_insert::<Library>(library)
+            .type_map_insert::<MusicLinkProviderKey>(music_link_provider)
+            .type_map_insert::<LyricsProviderKey>(lyrics_provider)
             .await
             .map_err(|err| CadencyError::Start {
                 source: Box::new(err),
@@ -48,6 +102,7 @@ impl Cadency {
 
         // Keep a handle to the shard manager so we can shut down gracefully on Ctrl+C
         let shard_manager = client.shard_manager.clone();
+        let data = client.data.clone();
 
         // Run the client's start future in a background task so we can await a signal concurrently
         let client_task = tokio::spawn(async move {
@@ -78,6 +133,23 @@ impl Cadency {
 
         info!("Received Ctrl+C - shutting down shards");
 
+        // Save the queues while the voice calls are still active
+        if let Some(queue_file) = &self.queue_file {
+            let (manager, guild_states) = {
+                let data = data.read().await;
+                (
+                    data.get::<songbird::SongbirdKey>().cloned(),
+                    data.get::<GuildStates>().cloned(),
+                )
+            };
+            if let (Some(manager), Some(guild_states)) = (manager, guild_states) {
+                match persistence::save_queues(&manager, &guild_states, queue_file).await {
+                    Ok(amount) => info!("Saved {amount} queues to {queue_file:?}"),
+                    Err(err) => error!("Failed to save queues: {err}"),
+                }
+            }
+        }
+
         // Ask the shard manager to shut down all shards gracefully
         shard_manager.shutdown_all().await;
 
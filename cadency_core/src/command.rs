@@ -7,7 +7,7 @@ use serenity::{
     all::{Builder, GuildId},
     async_trait,
     builder::{
-        CreateCommand, CreateCommandOption, CreateInteractionResponse,
+        AutocompleteChoice, CreateCommand, CreateCommandOption, CreateInteractionResponse,
         CreateInteractionResponseMessage,
     },
     client::Context,
@@ -42,6 +42,22 @@ pub struct CadencyCommandOption {
     pub description: &'static str,
     pub kind: CommandOptionType,
     pub required: bool,
+    /// Name of the subcommand option this option is nested in
+    pub subcommand: Option<&'static str>,
+    /// Suggestions for the option are requested from [`CadencyCommand::autocomplete`]
+    pub autocomplete: bool,
+}
+
+impl CadencyCommandOption {
+    fn to_create_option(&self) -> CreateCommandOption {
+        let create_option = CreateCommandOption::new(self.kind, self.name, self.description);
+        match self.kind {
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => create_option,
+            _ => create_option
+                .required(self.required)
+                .set_autocomplete(self.autocomplete),
+        }
+    }
 }
 
 #[async_trait]
@@ -52,12 +68,17 @@ pub trait CadencyCommand: Sync + Send + CadencyCommandBaseline {
         ctx: &Context,
         scope: CommandsScope,
     ) -> Result<Command, serenity::Error> {
-        let command_options: Vec<CreateCommandOption> = self
-            .options()
-            .into_iter()
+        let options = self.options();
+        let command_options: Vec<CreateCommandOption> = options
+            .iter()
+            .filter(|option| option.subcommand.is_none())
             .map(|option| {
-                CreateCommandOption::new(option.kind, option.name, option.description)
-                    .required(option.required)
+                options
+                    .iter()
+                    .filter(|sub_option| sub_option.subcommand == Some(option.name))
+                    .fold(option.to_create_option(), |create_option, sub_option| {
+                        create_option.add_sub_option(sub_option.to_create_option())
+                    })
             })
             .collect();
         let command_builder = CreateCommand::new(self.name())
@@ -79,6 +100,16 @@ pub trait CadencyCommand: Sync + Send + CadencyCommandBaseline {
         command: &'a mut CommandInteraction,
         response_builder: &'a mut ResponseBuilder,
     ) -> Result<Response, CadencyError>;
+
+    /// Suggestions for the option that is currently typed, see
+    /// <https://discord.com/developers/docs/interactions/application-commands#autocomplete>
+    async fn autocomplete(
+        &self,
+        _ctx: &Context,
+        _interaction: &CommandInteraction,
+    ) -> Vec<AutocompleteChoice> {
+        Vec::new()
+    }
 }
 
 pub(crate) struct Commands;
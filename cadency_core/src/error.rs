@@ -1,4 +1,5 @@
 use crate::response::ResponseBuilderError;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,4 +18,20 @@ pub enum CadencyError {
     Command { message: String },
     #[error("Response building failed")]
     ResponseBuilder(#[from] ResponseBuilderError),
+    #[error("Failed to fetch track metadata: {0}")]
+    Metadata(#[from] songbird::input::AuxMetadataError),
+    #[error("Failed to load audio stream: {0}")]
+    Stream(#[from] songbird::input::AudioStreamError),
+    #[error("User already has {limit} tracks in the queue")]
+    UserQueueLimit { limit: usize },
+    #[error("Track is longer than {limit:?}")]
+    SongTooLong { duration: Duration, limit: Duration },
+    #[error("Queue would be longer than {limit:?}")]
+    QueueTooLong { limit: Duration },
+    #[error("Length of the audio file is unknown")]
+    UnknownDuration,
+    #[error("File access failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize json: {0}")]
+    Json(#[from] serde_json::Error),
 }
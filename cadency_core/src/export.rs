@@ -0,0 +1,142 @@
+use crate::{error::CadencyError, track::QueuedTrack};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+use std::time::Duration;
+
+/// Track of an exported queue, readable by other tools
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedTrack {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// Duration in seconds
+    pub duration: Option<f64>,
+}
+
+impl From<&QueuedTrack> for ExportedTrack {
+    fn from(track: &QueuedTrack) -> Self {
+        Self {
+            title: track.title.clone(),
+            url: track.url.clone(),
+            duration: track.duration.map(|duration| duration.as_secs_f64()),
+        }
+    }
+}
+
+impl ExportedTrack {
+    /// Turn the track into a queueable track of the requester
+    pub fn into_queued_track(self, requester: UserId) -> QueuedTrack {
+        QueuedTrack {
+            title: self.title,
+            url: self.url,
+            requester,
+            playlist: None,
+            duration: self
+                .duration
+                .filter(|duration| duration.is_finite() && *duration >= 0.0)
+                .map(Duration::from_secs_f64),
+            verified: false,
+        }
+    }
+}
+
+pub fn to_json(tracks: &[ExportedTrack]) -> Result<String, CadencyError> {
+    Ok(serde_json::to_string_pretty(tracks)?)
+}
+
+/// Extended M3U playlist, tracks without url are left out
+pub fn to_m3u8(tracks: &[ExportedTrack]) -> String {
+    let mut m3u8 = String::from("#EXTM3U\n");
+    for track in tracks {
+        if let Some(url) = &track.url {
+            let duration = track
+                .duration
+                .map_or(-1, |duration| duration.round() as i64);
+            let title = track
+                .title
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', " ");
+            m3u8.push_str(&format!("#EXTINF:{duration},{title}\n{url}\n"));
+        }
+    }
+    m3u8
+}
+
+fn parse_m3u8(content: &str) -> Vec<ExportedTrack> {
+    let mut tracks = Vec::new();
+    let mut info: Option<(Option<f64>, Option<String>)> = None;
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            let (duration, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+            info = Some((
+                duration
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|duration| *duration >= 0.0),
+                Some(title.trim().to_string()).filter(|title| !title.is_empty()),
+            ));
+        } else if !line.starts_with('#') {
+            let (duration, title) = info.take().unwrap_or_default();
+            tracks.push(ExportedTrack {
+                title,
+                url: Some(line.to_string()),
+                duration,
+            });
+        }
+    }
+    tracks
+}
+
+/// Read the tracks of an exported queue, either the json or the M3U8 export
+pub fn parse(content: &str) -> Result<Vec<ExportedTrack>, CadencyError> {
+    if content.trim_start().starts_with('[') {
+        Ok(serde_json::from_str(content)?)
+    } else {
+        Ok(parse_m3u8(content))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tracks() -> Vec<ExportedTrack> {
+        vec![
+            ExportedTrack {
+                title: Some("First".to_string()),
+                url: Some("https://example.com/1".to_string()),
+                duration: Some(61.0),
+            },
+            ExportedTrack {
+                title: None,
+                url: Some("https://example.com/2".to_string()),
+                duration: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn should_read_exported_json() {
+        let json = to_json(&tracks()).unwrap();
+        assert_eq!(parse(&json).unwrap(), tracks());
+    }
+
+    #[test]
+    fn should_read_exported_m3u8() {
+        let m3u8 = to_m3u8(&tracks());
+        assert!(m3u8.starts_with("#EXTM3U\n#EXTINF:61,First\nhttps://example.com/1\n"));
+        assert_eq!(parse(&m3u8).unwrap(), tracks());
+    }
+
+    #[test]
+    fn should_read_plain_m3u() {
+        let parsed = parse("# comment\nhttps://example.com/a\n\nhttps://example.com/b\n").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].url.as_deref(), Some("https://example.com/b"));
+    }
+}
@@ -0,0 +1,534 @@
+use crate::guild::GuildStates;
+use serenity::{async_trait, model::id::GuildId};
+use songbird::input::{
+    codecs::{get_codec_registry, get_probe},
+    AudioStream, AudioStreamError, AuxMetadata, Compose, Input, RawAdapter,
+};
+use std::{
+    f32::consts::{PI, SQRT_2},
+    fmt,
+    io::{self, Read, Seek, SeekFrom},
+    str::FromStr,
+};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{Decoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader},
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+};
+
+/// Audio effects that can be applied to the playback of a guild
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FilterPreset {
+    #[default]
+    None,
+    BassBoost,
+    Nightcore,
+    Vaporwave,
+    EightD,
+}
+
+impl FilterPreset {
+    pub const ALL: [FilterPreset; 5] = [
+        FilterPreset::None,
+        FilterPreset::BassBoost,
+        FilterPreset::Nightcore,
+        FilterPreset::Vaporwave,
+        FilterPreset::EightD,
+    ];
+
+    /// Name that is used to select the preset in commands
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterPreset::None => "off",
+            FilterPreset::BassBoost => "bassboost",
+            FilterPreset::Nightcore => "nightcore",
+            FilterPreset::Vaporwave => "vaporwave",
+            FilterPreset::EightD => "8d",
+        }
+    }
+
+    /// Playback speed factor, changes tempo and pitch at the same time
+    fn speed(&self) -> f64 {
+        match self {
+            FilterPreset::Nightcore => 1.25,
+            FilterPreset::Vaporwave => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for FilterPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FilterPreset::None => "Off",
+            FilterPreset::BassBoost => "Bass Boost",
+            FilterPreset::Nightcore => "Nightcore",
+            FilterPreset::Vaporwave => "Vaporwave",
+            FilterPreset::EightD => "8D",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for FilterPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        FilterPreset::ALL
+            .into_iter()
+            .find(|preset| preset.as_str() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid filter '{}'. Must be one of: {}",
+                    s,
+                    FilterPreset::ALL.map(|preset| preset.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// Second order IIR filter, coefficients based on the "Audio EQ Cookbook" by Robert Bristow-Johnson
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn low_shelf(sample_rate: u32, frequency: f32, gain_db: f32) -> Self {
+        let a = 10_f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate as f32;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        // Shelf slope of 1 is the steepest slope without overshoot
+        let alpha = sin_w0 / 2.0 * SQRT_2;
+        let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        let a0 = (a + 1.0) + (a - 1.0) * cos_w0 + two_sqrt_a_alpha;
+        Self {
+            b0: a * ((a + 1.0) - (a - 1.0) * cos_w0 + two_sqrt_a_alpha) / a0,
+            b1: 2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0) / a0,
+            b2: a * ((a + 1.0) - (a - 1.0) * cos_w0 - two_sqrt_a_alpha) / a0,
+            a1: -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0) / a0,
+            a2: ((a + 1.0) + (a - 1.0) * cos_w0 - two_sqrt_a_alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let out = self.b0 * sample + self.z1;
+        self.z1 = self.b1 * sample - self.a1 * out + self.z2;
+        self.z2 = self.b2 * sample - self.a2 * out;
+        out
+    }
+}
+
+/// Changes the playback speed by linear interpolation between stereo frames
+#[derive(Debug)]
+struct Resampler {
+    speed: f64,
+    position: f64,
+    frames: Vec<[f32; 2]>,
+}
+
+impl Resampler {
+    fn new(speed: f64) -> Self {
+        Self {
+            speed,
+            position: 0.0,
+            frames: Vec::new(),
+        }
+    }
+
+    fn process(&mut self, input: &[[f32; 2]], output: &mut Vec<[f32; 2]>) {
+        self.frames.extend_from_slice(input);
+        while self.position + 1.0 < self.frames.len() as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            let (current, next) = (self.frames[index], self.frames[index + 1]);
+            output.push([
+                current[0] + (next[0] - current[0]) * fraction,
+                current[1] + (next[1] - current[1]) * fraction,
+            ]);
+            self.position += self.speed;
+        }
+        // Keep the frames that are still needed for the next interpolation
+        let consumed = (self.position as usize).min(self.frames.len());
+        self.frames.drain(..consumed);
+        self.position -= consumed as f64;
+    }
+}
+
+/// Moves the sound slowly around the listener by panning between the left and right channel
+#[derive(Debug)]
+struct Panner {
+    phase: f32,
+    phase_step: f32,
+}
+
+impl Panner {
+    /// Duration of one rotation around the listener in seconds
+    const ROTATION_PERIOD: f32 = 8.0;
+
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            phase: 0.0,
+            phase_step: 2.0 * PI / (Self::ROTATION_PERIOD * sample_rate as f32),
+        }
+    }
+
+    fn process(&mut self, frame: [f32; 2]) -> [f32; 2] {
+        // Equal power panning, both channels keep their original volume in the center position
+        let angle = (self.phase.sin() + 1.0) * PI / 4.0;
+        self.phase = (self.phase + self.phase_step) % (2.0 * PI);
+        [
+            frame[0] * angle.cos() * SQRT_2,
+            frame[1] * angle.sin() * SQRT_2,
+        ]
+    }
+}
+
+/// Chain of DSP stages that implements a [`FilterPreset`] on stereo frames
+#[derive(Debug)]
+pub(crate) struct FilterChain {
+    bass: Option<[Biquad; 2]>,
+    resampler: Option<Resampler>,
+    panner: Option<Panner>,
+}
+
+impl FilterChain {
+    pub(crate) fn new(preset: FilterPreset, sample_rate: u32) -> Self {
+        let speed = preset.speed();
+        Self {
+            bass: (preset == FilterPreset::BassBoost)
+                .then(|| [Biquad::low_shelf(sample_rate, 120.0, 8.0); 2]),
+            resampler: (speed != 1.0).then(|| Resampler::new(speed)),
+            panner: (preset == FilterPreset::EightD).then(|| Panner::new(sample_rate)),
+        }
+    }
+
+    pub(crate) fn process(&mut self, input: &[[f32; 2]], output: &mut Vec<[f32; 2]>) {
+        let start = output.len();
+        match self.resampler.as_mut() {
+            Some(resampler) => resampler.process(input, output),
+            None => output.extend_from_slice(input),
+        }
+        for frame in &mut output[start..] {
+            if let Some([left, right]) = self.bass.as_mut() {
+                *frame = [left.process(frame[0]), right.process(frame[1])];
+            }
+            if let Some(panner) = self.panner.as_mut() {
+                *frame = panner.process(*frame);
+            }
+            *frame = frame.map(|sample| sample.clamp(-1.0, 1.0));
+        }
+    }
+}
+
+/// Decodes an audio stream and exposes the filtered audio as raw interleaved `f32` stereo PCM
+struct FilterSource {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_rate: u32,
+    chain: FilterChain,
+    sample_buffer: Option<SampleBuffer<f32>>,
+    frames: Vec<[f32; 2]>,
+    filtered: Vec<[f32; 2]>,
+    bytes: Vec<u8>,
+    bytes_read: usize,
+}
+
+impl FilterSource {
+    fn new(
+        stream: AudioStream<Box<dyn MediaSource>>,
+        preset: FilterPreset,
+    ) -> Result<Self, SymphoniaError> {
+        let media_stream = MediaSourceStream::new(stream.input, Default::default());
+        let probed = get_probe().format(
+            &stream.hint.unwrap_or_default(),
+            media_stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+        let track = format
+            .default_track()
+            .ok_or(SymphoniaError::Unsupported("No default track"))?;
+        let sample_rate = track
+            .codec_params
+            .sample_rate
+            .ok_or(SymphoniaError::Unsupported("Unknown sample rate"))?;
+        let decoder = get_codec_registry().make(&track.codec_params, &DecoderOptions::default())?;
+        Ok(Self {
+            track_id: track.id,
+            format,
+            decoder,
+            sample_rate,
+            chain: FilterChain::new(preset, sample_rate),
+            sample_buffer: None,
+            frames: Vec::new(),
+            filtered: Vec::new(),
+            bytes: Vec::new(),
+            bytes_read: 0,
+        })
+    }
+
+    /// Decode and filter the next packet, returns `false` once the stream has ended
+    fn decode_next(&mut self) -> io::Result<bool> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    return Ok(false)
+                }
+                Err(err) => return Err(io::Error::other(err)),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // Corrupted packets are skipped, the decoder can recover with the next one
+                Err(SymphoniaError::DecodeError(err)) => {
+                    debug!("Skip undecodable audio packet: {err}");
+                    continue;
+                }
+                Err(err) => return Err(io::Error::other(err)),
+            };
+            let channels = decoded.spec().channels.count();
+            let buffer = match self.sample_buffer.as_mut() {
+                Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => buffer,
+                _ => self.sample_buffer.insert(SampleBuffer::new(
+                    decoded.capacity() as u64,
+                    *decoded.spec(),
+                )),
+            };
+            buffer.copy_interleaved_ref(decoded);
+
+            self.frames.clear();
+            self.frames
+                .extend(
+                    buffer
+                        .samples()
+                        .chunks_exact(channels)
+                        .map(|samples| match samples {
+                            [mono] => [*mono, *mono],
+                            [left, right, ..] => [*left, *right],
+                            [] => [0.0, 0.0],
+                        }),
+                );
+            self.filtered.clear();
+            self.chain.process(&self.frames, &mut self.filtered);
+
+            self.bytes.clear();
+            self.bytes_read = 0;
+            for sample in self.filtered.iter().flatten() {
+                self.bytes.extend_from_slice(&sample.to_le_bytes());
+            }
+            return Ok(true);
+        }
+    }
+}
+
+impl Read for FilterSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.bytes_read >= self.bytes.len() {
+            if !self.decode_next()? {
+                return Ok(0);
+            }
+        }
+        let remaining = &self.bytes[self.bytes_read..];
+        let amount = remaining.len().min(buf.len());
+        buf[..amount].copy_from_slice(&remaining[..amount]);
+        self.bytes_read += amount;
+        Ok(amount)
+    }
+}
+
+impl Seek for FilterSource {
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+impl MediaSource for FilterSource {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Lazy input that applies the current filter preset of a guild when the track is created
+pub struct FilteredInput {
+    inner: Box<dyn Compose>,
+    guild_states: GuildStates,
+    guild_id: GuildId,
+    /// Already known metadata, the inner input is not asked for it again
+    metadata: Option<AuxMetadata>,
+}
+
+impl FilteredInput {
+    pub fn new(inner: Box<dyn Compose>, guild_states: GuildStates, guild_id: GuildId) -> Self {
+        Self {
+            inner,
+            guild_states,
+            guild_id,
+            metadata: None,
+        }
+    }
+
+    pub fn with_metadata(self, metadata: AuxMetadata) -> Self {
+        Self {
+            metadata: Some(metadata),
+            ..self
+        }
+    }
+
+    fn apply_filter(
+        stream: AudioStream<Box<dyn MediaSource>>,
+        preset: FilterPreset,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        let source = FilterSource::new(stream, preset).map_err(|err| {
+            error!("Unable to decode audio for the '{preset}' filter: {err}");
+            AudioStreamError::Fail(Box::new(err))
+        })?;
+        let sample_rate = source.sample_rate;
+        Ok(AudioStream {
+            input: Box::new(RawAdapter::new(source, sample_rate, 2)),
+            hint: None,
+        })
+    }
+}
+
+impl From<FilteredInput> for Input {
+    fn from(filtered_input: FilteredInput) -> Self {
+        Input::Lazy(Box::new(filtered_input))
+    }
+}
+
+#[async_trait]
+impl Compose for FilteredInput {
+    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        let stream = self.inner.create()?;
+        match self
+            .guild_states
+            .get(self.guild_id, |state| state.settings.filter)
+        {
+            FilterPreset::None => Ok(stream),
+            preset => Self::apply_filter(stream, preset),
+        }
+    }
+
+    async fn create_async(
+        &mut self,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        let stream = self.inner.create_async().await?;
+        match self
+            .guild_states
+            .get(self.guild_id, |state| state.settings.filter)
+        {
+            FilterPreset::None => Ok(stream),
+            // Probing reads from the stream, which blocks the current thread
+            preset => tokio::task::spawn_blocking(move || Self::apply_filter(stream, preset))
+                .await
+                .map_err(|err| AudioStreamError::Fail(Box::new(err)))?,
+        }
+    }
+
+    fn should_create_async(&self) -> bool {
+        self.inner.should_create_async()
+    }
+
+    async fn aux_metadata(&mut self) -> Result<AuxMetadata, AudioStreamError> {
+        if let Some(metadata) = &self.metadata {
+            return Ok(metadata.clone());
+        }
+        self.inner.aux_metadata().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sine_frames(amount: usize) -> Vec<[f32; 2]> {
+        (0..amount)
+            .map(|index| {
+                let sample = (index as f32 * 0.05).sin() * 0.5;
+                [sample, sample]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_presets_by_name() {
+        assert_eq!("Nightcore".parse(), Ok(FilterPreset::Nightcore));
+        assert_eq!(" 8d ".parse(), Ok(FilterPreset::EightD));
+        assert_eq!("off".parse(), Ok(FilterPreset::None));
+        assert!("chipmunk".parse::<FilterPreset>().is_err());
+    }
+
+    #[test]
+    fn should_not_change_audio_without_filter() {
+        let input = sine_frames(1000);
+        let mut output = Vec::new();
+        FilterChain::new(FilterPreset::None, 48000).process(&input, &mut output);
+        assert_eq!(input, output);
+    }
+
+    #[test]
+    fn should_shorten_audio_for_nightcore() {
+        let input = sine_frames(10000);
+        let mut output = Vec::new();
+        let mut chain = FilterChain::new(FilterPreset::Nightcore, 48000);
+        for chunk in input.chunks(1024) {
+            chain.process(chunk, &mut output);
+        }
+        assert_eq!(output.len(), 8000);
+    }
+
+    #[test]
+    fn should_lengthen_audio_for_vaporwave() {
+        let input = sine_frames(8000);
+        let mut output = Vec::new();
+        FilterChain::new(FilterPreset::Vaporwave, 48000).process(&input, &mut output);
+        assert_eq!(output.len(), 9999);
+    }
+
+    #[test]
+    fn should_keep_samples_in_range() {
+        let input = vec![[1.0, -1.0]; 48000];
+        for preset in FilterPreset::ALL {
+            let mut output = Vec::new();
+            FilterChain::new(preset, 48000).process(&input, &mut output);
+            assert!(output
+                .iter()
+                .flatten()
+                .all(|sample| (-1.0..=1.0).contains(sample)));
+        }
+    }
+
+    #[test]
+    fn should_pan_between_channels_for_8d() {
+        let input = vec![[0.5, 0.5]; 48000 * 4];
+        let mut output = Vec::new();
+        FilterChain::new(FilterPreset::EightD, 48000).process(&input, &mut output);
+        // After a quarter rotation the sound is completely on the right channel
+        let [left, right] = output[48000 * 2];
+        assert!(left.abs() < 0.01, "Left channel should be silent: {left}");
+        assert!(right > 0.7, "Right channel should be louder: {right}");
+    }
+}
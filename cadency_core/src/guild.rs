@@ -0,0 +1,233 @@
+use crate::filter::FilterPreset;
+use serenity::{
+    client::Context,
+    model::{
+        guild::Member,
+        id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+        Timestamp,
+    },
+    prelude::TypeMapKey,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Maximum amount of finished tracks that are remembered per guild
+pub const HISTORY_LIMIT: usize = 50;
+
+/// A track that has been played in a guild
+#[derive(Debug, Clone)]
+pub struct PlayedTrack {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub requester: UserId,
+    pub finished_at: Timestamp,
+}
+
+/// Music settings of a guild, the defaults are configured when building cadency
+#[derive(Debug, Clone)]
+pub struct GuildSettings {
+    /// Audio filter that is applied to tracks when they start playing
+    pub filter: FilterPreset,
+    /// Interleave the queued tracks of different requesters
+    pub fair_queue: bool,
+    /// Maximum amount of tracks a single user can have in the queue
+    pub user_queue_limit: Option<usize>,
+    /// Fraction of the listeners that have to vote to skip a track, skip immediately if not set
+    pub vote_skip_threshold: Option<f32>,
+    /// Members with this role can control the playback without restrictions
+    pub dj_role: Option<RoleId>,
+    /// Only users in the voice channel of the bot can control the playback
+    pub same_channel_only: bool,
+    /// How long the bot stays in a voice channel without listeners before it leaves
+    pub empty_channel_timeout: Duration,
+    /// How long the bot stays in a voice channel without tracks before it leaves
+    pub inactive_timeout: Duration,
+    /// Stay in the voice channel 24/7 instead of leaving when inactive
+    pub stay: bool,
+    /// Longer tracks can only be added by DJs
+    pub song_length_limit: Option<Duration>,
+    /// Tracks that make the queue longer can only be added by DJs
+    pub max_queue_duration: Option<Duration>,
+    /// Add a related track when the last track of the queue starts playing
+    pub autoplay: bool,
+    /// Keep a message with the current track and playback buttons in the music channel
+    pub now_playing_panel: bool,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            filter: FilterPreset::default(),
+            fair_queue: false,
+            user_queue_limit: None,
+            vote_skip_threshold: None,
+            dj_role: None,
+            same_channel_only: false,
+            empty_channel_timeout: Duration::from_secs(300),
+            inactive_timeout: Duration::from_secs(120),
+            stay: false,
+            song_length_limit: None,
+            max_queue_duration: None,
+            autoplay: false,
+            now_playing_panel: false,
+        }
+    }
+}
+
+impl GuildSettings {
+    /// Check if the member has the DJ role or is an administrator of the guild
+    pub fn is_dj(&self, member: Option<&Member>) -> bool {
+        member.is_some_and(|member| {
+            member
+                .permissions
+                .is_some_and(|permissions| permissions.administrator())
+                || self
+                    .dj_role
+                    .is_some_and(|dj_role| member.roles.contains(&dj_role))
+        })
+    }
+}
+
+/// Music related state of a single guild
+#[derive(Debug, Default, Clone)]
+pub struct GuildState {
+    pub settings: GuildSettings,
+    /// Finished tracks, the most recent track is at the back
+    pub history: VecDeque<PlayedTrack>,
+    /// Since when the bot is alone in its voice channel
+    pub alone_since: Option<Instant>,
+    /// The playback was paused because nobody was listening
+    pub paused_while_alone: bool,
+    /// The voice call of the guild already has the global event handlers
+    pub voice_handlers_registered: bool,
+    /// Text channel where the music was started
+    pub text_channel: Option<ChannelId>,
+    /// Changes whenever the queue is cleared, tracks of older generations are not added anymore
+    pub queue_generation: u64,
+    /// Message of the now playing panel
+    pub panel_message: Option<(ChannelId, MessageId)>,
+    /// Related tracks are being looked up for autoplay
+    pub autoplay_in_flight: bool,
+}
+
+impl GuildState {
+    fn new(settings: GuildSettings) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    /// Remember a finished track and forget the oldest one once the history is full
+    pub fn add_to_history(&mut self, track: PlayedTrack) {
+        if self.history.len() >= HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(track);
+    }
+}
+
+/// Shared storage of all guild states.
+/// The storage can be cloned cheaply and is used by commands as well as by voice event handlers.
+#[derive(Debug, Default, Clone)]
+pub struct GuildStates {
+    inner: Arc<RwLock<HashMap<GuildId, GuildState>>>,
+    default_settings: Arc<GuildSettings>,
+}
+
+impl TypeMapKey for GuildStates {
+    type Value = GuildStates;
+}
+
+impl GuildStates {
+    pub fn new(default_settings: GuildSettings) -> Self {
+        Self {
+            inner: Default::default(),
+            default_settings: Arc::new(default_settings),
+        }
+    }
+
+    /// Read the state of a guild. Guilds without a stored state are handled as default state.
+    pub fn get<T>(&self, guild_id: GuildId, read: impl FnOnce(&GuildState) -> T) -> T {
+        let states = self.inner.read().expect("Guild states lock poisoned");
+        match states.get(&guild_id) {
+            Some(state) => read(state),
+            None => read(&GuildState::new(self.default_settings.as_ref().clone())),
+        }
+    }
+
+    /// Modify the state of a guild, creating a default state if the guild has none yet
+    pub fn update<T>(&self, guild_id: GuildId, write: impl FnOnce(&mut GuildState) -> T) -> T {
+        let mut states = self.inner.write().expect("Guild states lock poisoned");
+        write(
+            states
+                .entry(guild_id)
+                .or_insert_with(|| GuildState::new(self.default_settings.as_ref().clone())),
+        )
+    }
+}
+
+pub async fn get_guild_states(ctx: &Context) -> GuildStates {
+    let data = ctx.data.read().await;
+    data.get::<GuildStates>()
+        .expect("Expected GuildStates in TypeMap.")
+        .clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_return_default_state_for_unknown_guild() {
+        let states = GuildStates::default();
+        let filter = states.get(GuildId::new(1), |state| state.settings.filter);
+        assert_eq!(filter, FilterPreset::None);
+    }
+
+    #[test]
+    fn should_keep_updated_state_per_guild() {
+        let states = GuildStates::default();
+        states.update(GuildId::new(1), |state| {
+            state.settings.filter = FilterPreset::Nightcore
+        });
+        assert_eq!(
+            states.get(GuildId::new(1), |state| state.settings.filter),
+            FilterPreset::Nightcore
+        );
+        assert_eq!(
+            states.get(GuildId::new(2), |state| state.settings.filter),
+            FilterPreset::None
+        );
+    }
+
+    #[test]
+    fn should_use_default_settings_for_new_guilds() {
+        let states = GuildStates::new(GuildSettings {
+            fair_queue: true,
+            ..Default::default()
+        });
+        assert!(states.get(GuildId::new(1), |state| state.settings.fair_queue));
+        states.update(GuildId::new(1), |state| state.settings.fair_queue = false);
+        assert!(!states.get(GuildId::new(1), |state| state.settings.fair_queue));
+        assert!(states.get(GuildId::new(2), |state| state.settings.fair_queue));
+    }
+
+    #[test]
+    fn should_limit_history_length() {
+        let mut state = GuildState::default();
+        for index in 0..HISTORY_LIMIT + 5 {
+            state.add_to_history(PlayedTrack {
+                title: Some(index.to_string()),
+                url: None,
+                requester: UserId::new(1),
+                finished_at: Timestamp::now(),
+            });
+        }
+        assert_eq!(state.history.len(), HISTORY_LIMIT);
+        assert_eq!(state.history.front().unwrap().title.as_deref(), Some("5"));
+    }
+}
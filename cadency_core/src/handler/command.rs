@@ -1,21 +1,24 @@
 use crate::{
     command::{command_not_implemented, setup_commands},
+    handler::voice::check_listeners,
+    panel, persistence,
     response::{ResponseBuilder, ResponseTiming},
     utils, CadencyError,
 };
 use serenity::{
     all::OnlineStatus,
     async_trait,
+    builder::{CreateAutocompleteResponse, CreateInteractionResponse},
     client::{Context, EventHandler},
     gateway::ActivityData,
-    model::{application::Interaction, event::ResumedEvent, gateway::Ready},
+    model::{application::Interaction, event::ResumedEvent, gateway::Ready, voice::VoiceState},
 };
 
 pub(crate) struct Handler;
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, ctx: Context, _data_about_bot: Ready) {
+    async fn ready(&self, ctx: Context, data_about_bot: Ready) {
         info!("🚀 Start Cadency Discord Bot");
         // Set the bot presence to "Listening to music"
         ctx.set_presence(Some(ActivityData::listening("music")), OnlineStatus::Online);
@@ -25,14 +28,44 @@ impl EventHandler for Handler {
             Ok(()) => info!("✅ Application commands submitted"),
             Err(err) => error!("❌ Failed to submit application commands: {:?}", err),
         };
+        persistence::resume_queues(&ctx, data_about_bot.shard).await;
     }
 
     async fn resume(&self, _ctx: Context, _: ResumedEvent) {
         debug!("🔌 Reconnect to server");
     }
 
+    async fn voice_state_update(&self, ctx: Context, _old: Option<VoiceState>, new: VoiceState) {
+        if let Some(guild_id) = new.guild_id {
+            check_listeners(&ctx, guild_id).await;
+        }
+    }
+
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        if let Interaction::Command(mut command) = interaction {
+        if let Interaction::Component(component) = interaction {
+            // Components of a command response are handled by the collectors of the command
+            if component
+                .data
+                .custom_id
+                .starts_with(panel::CUSTOM_ID_PREFIX)
+            {
+                panel::handle_interaction(&ctx, &component).await;
+            }
+        } else if let Interaction::Autocomplete(autocomplete) = interaction {
+            let cmd_target = utils::get_commands(&ctx)
+                .await
+                .into_iter()
+                .find(|cadency_command| cadency_command.name() == autocomplete.data.name.as_str());
+            if let Some(cmd) = cmd_target {
+                let choices = cmd.autocomplete(&ctx, &autocomplete).await;
+                let response = CreateInteractionResponse::Autocomplete(
+                    CreateAutocompleteResponse::new().set_choices(choices),
+                );
+                if let Err(err) = autocomplete.create_response(&ctx.http, response).await {
+                    error!("❌ Failed to submit autocomplete suggestions: {err}");
+                }
+            }
+        } else if let Interaction::Command(mut command) = interaction {
             let cmd_target = utils::get_commands(&ctx)
                 .await
                 .into_iter()
@@ -1,23 +1,433 @@
-use serenity::async_trait;
-use songbird::events::{Event, EventContext};
+use crate::{
+    guild::{get_guild_states, GuildStates, PlayedTrack},
+    panel,
+    track::TrackData,
+    utils,
+};
+use serenity::{
+    async_trait,
+    builder::{CreateEmbed, CreateMessage},
+    client::Context,
+    model::{colour::Colour, id::GuildId, Timestamp},
+};
+use songbird::{
+    events::{context_data::DisconnectReason, CoreEvent, Event, EventContext, TrackEvent},
+    tracks::PlayMode,
+    Call,
+};
+use std::time::{Duration, Instant};
 
+/// How often the inactive handler checks if the bot has nothing to play
+const INACTIVE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Leaves the voice channel when there was nothing to play for the inactivity timeout of the guild
 pub struct InactiveHandler {
     pub manager: std::sync::Arc<songbird::Songbird>,
     pub guild_id: serenity::model::id::GuildId,
+    pub guild_states: GuildStates,
+    inactive_since: std::sync::Mutex<Option<Instant>>,
+}
+
+impl InactiveHandler {
+    pub fn new(
+        manager: std::sync::Arc<songbird::Songbird>,
+        guild_id: GuildId,
+        guild_states: GuildStates,
+    ) -> Self {
+        Self {
+            manager,
+            guild_id,
+            guild_states,
+            inactive_since: std::sync::Mutex::default(),
+        }
+    }
+}
+
+/// Leave the voice channel and drop the call with its event handlers,
+/// the handlers are registered again when the bot joins the next time
+async fn leave_voice_channel(
+    manager: &songbird::Songbird,
+    guild_states: &GuildStates,
+    guild_id: GuildId,
+) {
+    if let Err(err) = manager.remove(guild_id).await {
+        error!("Failed to leave voice channel: {err:?}");
+    }
+    guild_states.update(guild_id, |state| state.voice_handlers_registered = false);
+}
+
+/// Add the global voice event handlers to the call unless the guild already has them.
+/// Other global events of the call are kept.
+pub async fn register_voice_handlers(ctx: &Context, call: &mut Call, guild_id: GuildId) {
+    let guild_states = get_guild_states(ctx).await;
+    let is_registered = guild_states.update(guild_id, |state| {
+        std::mem::replace(&mut state.voice_handlers_registered, true)
+    });
+    if is_registered {
+        return;
+    }
+    debug!("Register voice handlers for guild {guild_id}");
+    let manager = utils::voice::get_songbird(ctx).await;
+    call.add_global_event(
+        Event::Periodic(INACTIVE_CHECK_INTERVAL, None),
+        InactiveHandler::new(manager.clone(), guild_id, guild_states),
+    );
+    // Global track events are sent for every track of the call
+    for track_event in [TrackEvent::Play, TrackEvent::Pause, TrackEvent::End] {
+        call.add_global_event(
+            Event::Track(track_event),
+            NowPlayingPanelHandler {
+                ctx: ctx.clone(),
+                guild_id,
+            },
+        );
+    }
+    call.add_global_event(
+        Event::Core(CoreEvent::DriverDisconnect),
+        ReconnectHandler {
+            ctx: ctx.clone(),
+            manager,
+            guild_id,
+        },
+    );
 }
 
 #[async_trait]
 impl songbird::EventHandler for InactiveHandler {
     async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
         if let songbird::EventContext::Track(track_list) = ctx {
-            if track_list.is_empty() {
+            let (stay, timeout) = self.guild_states.get(self.guild_id, |state| {
+                (state.settings.stay, state.settings.inactive_timeout)
+            });
+            let is_inactive = {
+                let mut inactive_since = self
+                    .inactive_since
+                    .lock()
+                    .expect("Inactive since lock poisoned");
+                if stay || !track_list.is_empty() {
+                    *inactive_since = None;
+                    false
+                } else {
+                    let since = inactive_since.get_or_insert_with(Instant::now);
+                    if since.elapsed() >= timeout {
+                        *inactive_since = None;
+                        true
+                    } else {
+                        false
+                    }
+                }
+            };
+            if is_inactive {
                 debug!("Tracklist empty, leave channel!");
-                if let Some(call) = self.manager.get(self.guild_id) {
-                    let mut handler = call.lock().await;
-                    handler.leave().await.unwrap();
+                leave_voice_channel(&self.manager, &self.guild_states, self.guild_id).await;
+                // The handlers of the dropped call are not needed anymore
+                return Some(Event::Cancel);
+            }
+        }
+        None
+    }
+}
+
+/// Delays between the attempts to rejoin the voice channel after the connection dropped
+const RECONNECT_BACKOFF: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+];
+
+/// Rejoins the voice channel when the connection was lost and restores the queue if needed
+pub struct ReconnectHandler {
+    pub ctx: Context,
+    pub manager: std::sync::Arc<songbird::Songbird>,
+    pub guild_id: GuildId,
+}
+
+#[async_trait]
+impl songbird::EventHandler for ReconnectHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        if let EventContext::DriverDisconnect(disconnect) = ctx {
+            // Leaving or moving the bot on purpose is no connection failure
+            let is_failure = !matches!(disconnect.reason, None | Some(DisconnectReason::Requested));
+            if let (true, Some(channel_id)) = (is_failure, disconnect.channel_id) {
+                warn!(
+                    "Voice connection in guild {} dropped: {:?}",
+                    self.guild_id, disconnect.reason
+                );
+                let call = self.manager.get(self.guild_id)?;
+                let snapshot = {
+                    let handler = call.lock().await;
+                    utils::queue::snapshot_queue(handler.queue()).await
+                };
+                // Joining waits for the connection, so the event task must not be blocked
+                tokio::spawn(reconnect(
+                    self.ctx.clone(),
+                    self.manager.clone(),
+                    self.guild_id,
+                    channel_id,
+                    snapshot,
+                ));
+            }
+        }
+        None
+    }
+}
+
+async fn reconnect(
+    ctx: Context,
+    manager: std::sync::Arc<songbird::Songbird>,
+    guild_id: GuildId,
+    channel_id: songbird::id::ChannelId,
+    snapshot: utils::queue::QueueSnapshot,
+) {
+    let mut is_connected = false;
+    for delay in RECONNECT_BACKOFF {
+        tokio::time::sleep(delay).await;
+        match manager.join(guild_id, channel_id).await {
+            Ok(_) => {
+                is_connected = true;
+                break;
+            }
+            Err(err) => warn!("Failed to rejoin voice channel, retry in a moment: {err:?}"),
+        }
+    }
+
+    let description = match manager.get(guild_id).filter(|_| is_connected) {
+        Some(call) => {
+            let restored = utils::queue::restore_queue(&ctx, call, guild_id, snapshot).await;
+            info!("Rejoined voice channel in guild {guild_id}, restored {restored} tracks");
+            if restored > 0 {
+                format!("✅ **Reconnected**\n\nThe connection dropped, **{restored}** tracks have been restored.")
+            } else {
+                "✅ **Reconnected**\n\nThe connection dropped, the playback continues.".to_string()
+            }
+        }
+        None => {
+            error!("Unable to rejoin voice channel in guild {guild_id}");
+            "❌ **Connection lost**\n\nI could not rejoin the voice channel.".to_string()
+        }
+    };
+    let text_channel = get_guild_states(&ctx)
+        .await
+        .get(guild_id, |state| state.text_channel);
+    if let Some(text_channel) = text_channel {
+        let embed = CreateEmbed::default()
+            .title("🔌 Voice Connection")
+            .color(Colour::from_rgb(100, 149, 237)) // Cornflower blue
+            .description(description);
+        if let Err(err) = text_channel
+            .send_message(&ctx.http, CreateMessage::new().embed(embed))
+            .await
+        {
+            error!("Failed to send reconnect message: {err:?}");
+        }
+    }
+}
+
+/// Adds a track to the play history of the guild once it ends
+pub struct HistoryHandler {
+    pub guild_states: GuildStates,
+    pub guild_id: GuildId,
+}
+
+#[async_trait]
+impl songbird::EventHandler for HistoryHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        if let songbird::EventContext::Track(track_list) = ctx {
+            // Stopping the queue also ends tracks that never started, those are not history
+            for (_, track) in track_list
+                .iter()
+                .filter(|(track_state, _)| !track_state.play_time.is_zero())
+            {
+                let track_data = track.data::<TrackData>();
+                // Replayed tracks stay out, so /previous keeps going further back.
+                // Uploaded files are left out as well, their urls expire.
+                let is_expiring = track_data
+                    .url()
+                    .is_some_and(crate::utils::audio::is_expiring_url);
+                if track_data.is_replayed() || is_expiring {
+                    continue;
                 }
+                debug!("Add track {:?} to the play history", track_data.title());
+                self.guild_states.update(self.guild_id, |state| {
+                    state.add_to_history(PlayedTrack {
+                        title: track_data.title().map(str::to_owned),
+                        url: track_data.url().map(str::to_owned),
+                        requester: track_data.requester,
+                        finished_at: Timestamp::now(),
+                    })
+                });
             }
         }
         None
     }
 }
+
+/// Removes a track from the queue when its stream can't be played and continues with the next
+pub struct UnavailableTrackHandler {
+    pub manager: std::sync::Arc<songbird::Songbird>,
+    pub guild_id: GuildId,
+}
+
+#[async_trait]
+impl songbird::EventHandler for UnavailableTrackHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let songbird::EventContext::Track(track_list) = ctx else {
+            return None;
+        };
+        let call = self.manager.get(self.guild_id)?;
+        let handler = call.lock().await;
+        for (_, track) in track_list.iter() {
+            warn!(
+                "Track {:?} is unavailable, remove it from the queue",
+                track.data::<TrackData>().title()
+            );
+            let was_current = handler.queue().modify_queue(|queue| {
+                let position = queue
+                    .iter()
+                    .position(|queued| queued.uuid() == track.uuid());
+                if let Some(position) = position {
+                    queue.remove(position);
+                }
+                position == Some(0)
+            });
+            // The queue only continues by itself when a track ends
+            if was_current {
+                let _ = handler.queue().resume();
+            }
+        }
+        None
+    }
+}
+
+/// Shows track changes in the now playing panel of the guild
+pub struct NowPlayingPanelHandler {
+    pub ctx: Context,
+    pub guild_id: GuildId,
+}
+
+#[async_trait]
+impl songbird::EventHandler for NowPlayingPanelHandler {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        panel::refresh(&self.ctx, self.guild_id).await;
+        None
+    }
+}
+
+/// Adds a related track once the last track of the queue starts playing and autoplay is enabled
+pub struct AutoplayHandler {
+    pub ctx: Context,
+    pub guild_id: GuildId,
+}
+
+#[async_trait]
+impl songbird::EventHandler for AutoplayHandler {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        // Looking up related tracks takes a while, the events of the call must not wait for it
+        let (ctx, guild_id) = (self.ctx.clone(), self.guild_id);
+        tokio::spawn(async move { utils::autoplay::queue_related_track(&ctx, guild_id).await });
+        None
+    }
+}
+
+/// Follows the announced titles of a radio stream while it plays
+pub struct StreamTitleHandler {
+    pub client: reqwest::Client,
+}
+
+#[async_trait]
+impl songbird::EventHandler for StreamTitleHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        if let songbird::EventContext::Track(track_list) = ctx {
+            for (_, track) in track_list.iter() {
+                let track_data = track.data::<TrackData>();
+                // The play event is sent again after a pause
+                let is_new_watch = track_data
+                    .live
+                    .as_ref()
+                    .is_some_and(|live| live.start_watching());
+                if is_new_watch {
+                    tokio::spawn(utils::radio::watch_stream_title(
+                        self.client.clone(),
+                        (*track).clone(),
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Pause the playback once the bot is alone in its voice channel and leave after the
+/// configured timeout. The playback continues if someone joins before the timeout.
+pub(crate) async fn check_listeners(ctx: &Context, guild_id: GuildId) {
+    let manager = utils::voice::get_songbird(ctx).await;
+    let Some(call) = manager.get(guild_id) else {
+        return;
+    };
+    let guild_states = get_guild_states(ctx).await;
+    let handler = call.lock().await;
+    if handler.current_connection().is_none() {
+        guild_states.update(guild_id, |state| {
+            state.alone_since = None;
+            state.paused_while_alone = false;
+        });
+        return;
+    }
+
+    if !utils::voice::get_bot_listeners(ctx, guild_id).is_empty() {
+        let resume = guild_states.update(guild_id, |state| {
+            state.alone_since.take().is_some() && std::mem::take(&mut state.paused_while_alone)
+        });
+        if resume {
+            debug!("Listener returned, resume playback");
+            if let Err(err) = handler.queue().resume() {
+                error!("Failed to resume playback: {err:?}");
+            }
+        }
+        return;
+    }
+
+    let alone_since = Instant::now();
+    let (is_new, timeout) = guild_states.update(guild_id, |state| {
+        let is_new = state.alone_since.is_none();
+        if is_new {
+            state.alone_since = Some(alone_since);
+        }
+        (is_new, state.settings.empty_channel_timeout)
+    });
+    if !is_new {
+        return;
+    }
+    debug!("Bot is alone in the voice channel, pause playback");
+    if let Some(track) = handler.queue().current() {
+        let is_playing = track
+            .get_info()
+            .await
+            .is_ok_and(|track_state| track_state.playing == PlayMode::Play);
+        if is_playing && handler.queue().pause().is_ok() {
+            guild_states.update(guild_id, |state| state.paused_while_alone = true);
+        }
+    }
+
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let still_alone = guild_states.update(guild_id, |state| {
+            // The playback stays paused in 24/7 mode until someone returns
+            let still_alone = state.alone_since == Some(alone_since) && !state.settings.stay;
+            if still_alone {
+                state.alone_since = None;
+                state.paused_while_alone = false;
+            }
+            still_alone
+        });
+        if still_alone {
+            debug!("Nobody returned to the voice channel, leave channel!");
+            if let Some(call) = manager.get(guild_id) {
+                call.lock().await.queue().stop();
+                leave_voice_channel(&manager, &guild_states, guild_id).await;
+            }
+        }
+    });
+}
@@ -8,8 +8,18 @@ mod command;
 pub use command::{CadencyCommand, CadencyCommandBaseline, CadencyCommandOption, CommandsScope};
 mod error;
 pub use error::CadencyError;
+pub mod export;
+pub mod filter;
+pub mod guild;
 pub mod handler;
 pub mod http;
 mod intents;
+pub mod library;
+pub mod lyrics;
+pub mod music_link;
+pub mod panel;
+pub mod persistence;
+pub mod playlist;
 pub mod response;
+pub mod track;
 pub mod utils;
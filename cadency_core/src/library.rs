@@ -0,0 +1,240 @@
+use crate::{error::CadencyError, utils};
+use reqwest::Url;
+use serenity::{client::Context, prelude::TypeMapKey};
+use songbird::input::AuxMetadata;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// File extensions of audio files that are added to the library
+const AUDIO_EXTENSIONS: [&str; 9] = [
+    "aac", "flac", "m4a", "mka", "mp3", "oga", "ogg", "opus", "wav",
+];
+
+/// Audio file of the local music library
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryTrack {
+    pub path: PathBuf,
+    /// Path of the file relative to the library root
+    pub id: String,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl LibraryTrack {
+    fn new(path: PathBuf, id: String, metadata: AuxMetadata) -> Self {
+        let title = metadata.title.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|file_stem| file_stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| id.clone())
+        });
+        Self {
+            path,
+            id,
+            title,
+            artist: metadata.artist,
+            album: metadata.album,
+            duration: metadata.duration,
+        }
+    }
+
+    /// `file://` url of the track, used to add the track to the queue
+    pub fn url(&self) -> String {
+        Url::from_file_path(&self.path)
+            .map(String::from)
+            .unwrap_or_else(|_| format!("file://{}", self.path.display()))
+    }
+
+    /// Lyrics of the track are read from a `.lrc` file with the same name
+    pub fn lyrics_path(&self) -> PathBuf {
+        self.path.with_extension("lrc")
+    }
+
+    /// Artist and title of the track
+    pub fn label(&self) -> String {
+        match &self.artist {
+            Some(artist) => format!("{artist} - {}", self.title),
+            None => self.title.clone(),
+        }
+    }
+
+    pub fn metadata(&self) -> AuxMetadata {
+        AuxMetadata {
+            title: Some(self.label()),
+            artist: self.artist.clone(),
+            album: self.album.clone(),
+            duration: self.duration,
+            source_url: Some(self.url()),
+            ..Default::default()
+        }
+    }
+
+    fn matches(&self, words: &[String]) -> bool {
+        let searchable = format!(
+            "{} {} {} {}",
+            self.title,
+            self.artist.as_deref().unwrap_or_default(),
+            self.album.as_deref().unwrap_or_default(),
+            self.id
+        )
+        .to_lowercase();
+        words.iter().all(|word| searchable.contains(word))
+    }
+}
+
+/// Audio files in a local folder, indexed once at startup
+#[derive(Debug, Default, Clone)]
+pub struct Library {
+    tracks: Arc<Vec<LibraryTrack>>,
+}
+
+impl TypeMapKey for Library {
+    type Value = Library;
+}
+
+impl Library {
+    /// Read the tags of all audio files in the folder and its sub folders.
+    /// Files that can't be read are left out.
+    pub fn index(root: &Path) -> Result<Self, CadencyError> {
+        let root = root.canonicalize()?;
+        let mut tracks = Vec::new();
+        let mut folders = vec![root.clone()];
+        while let Some(folder) = folders.pop() {
+            for entry in std::fs::read_dir(&folder)?.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    folders.push(path);
+                    continue;
+                }
+                let is_audio_file = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| {
+                        AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+                    });
+                if !is_audio_file {
+                    continue;
+                }
+                let id = path
+                    .strip_prefix(&root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                match utils::audio::read_file_metadata(&path) {
+                    Ok(metadata) => tracks.push(LibraryTrack::new(path, id, metadata)),
+                    Err(err) => warn!("Skip unreadable library file '{id}': {err}"),
+                }
+            }
+        }
+        tracks.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(Self::from_tracks(tracks))
+    }
+
+    fn from_tracks(tracks: Vec<LibraryTrack>) -> Self {
+        Self {
+            tracks: Arc::new(tracks),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// Tracks whose title, artist, album or path contain all words of the query
+    pub fn search(&self, query: &str, limit: usize) -> Vec<LibraryTrack> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.tracks
+            .iter()
+            .filter(|track| track.matches(&words))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Track with the id or otherwise the best search result for the query
+    pub fn find(&self, query: &str) -> Option<LibraryTrack> {
+        self.tracks
+            .iter()
+            .find(|track| track.id == query)
+            .cloned()
+            .or_else(|| self.search(query, 1).pop())
+    }
+
+    /// Track of a `file://` url, only files of the library can be played
+    pub fn get_by_url(&self, url: &str) -> Option<LibraryTrack> {
+        let path = Url::parse(url).ok()?.to_file_path().ok()?;
+        self.tracks.iter().find(|track| track.path == path).cloned()
+    }
+}
+
+pub async fn get_library(ctx: &Context) -> Library {
+    let data = ctx.data.read().await;
+    data.get::<Library>()
+        .expect("Expected Library in TypeMap.")
+        .clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn track(id: &str, title: &str, artist: Option<&str>) -> LibraryTrack {
+        LibraryTrack {
+            path: PathBuf::from(format!("/music/{id}")),
+            id: id.to_string(),
+            title: title.to_string(),
+            artist: artist.map(str::to_owned),
+            album: Some("Kind of Blue".to_string()),
+            duration: None,
+        }
+    }
+
+    fn library() -> Library {
+        Library::from_tracks(vec![
+            track("jazz/so_what.mp3", "So What", Some("Miles Davis")),
+            track(
+                "jazz/blue_in_green.flac",
+                "Blue in Green",
+                Some("Miles Davis"),
+            ),
+            track("rock/unknown.mp3", "unknown", None),
+        ])
+    }
+
+    #[test]
+    fn should_search_all_words_in_tags() {
+        let library = library();
+        let found = library.search("miles green", 10);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Blue in Green");
+        assert_eq!(library.search("kind of blue", 10).len(), 3);
+        assert_eq!(library.search("ROCK", 10).len(), 1);
+        assert_eq!(library.search("miles", 1).len(), 1);
+    }
+
+    #[test]
+    fn should_prefer_exact_id() {
+        let library = library();
+        assert_eq!(library.find("rock/unknown.mp3").unwrap().title, "unknown");
+        assert_eq!(library.find("so what").unwrap().id, "jazz/so_what.mp3");
+    }
+
+    #[test]
+    fn should_only_play_library_files() {
+        let library = library();
+        let track = library.find("so what").unwrap();
+        assert_eq!(library.get_by_url(&track.url()), Some(track));
+        assert!(library.get_by_url("file:///etc/passwd").is_none());
+        assert!(library
+            .get_by_url("https://example.com/music/so_what.mp3")
+            .is_none());
+    }
+}
@@ -0,0 +1,365 @@
+use crate::{error::CadencyError, library::get_library, track::TrackData};
+use serde_json::Value;
+use serenity::{async_trait, client::Context, prelude::TypeMapKey};
+use songbird::input::AuxMetadata;
+use std::{sync::Arc, time::Duration};
+
+/// Discord allows up to 4096 characters in embed descriptions
+const MAX_PAGE_LENGTH: usize = 2000;
+const LINES_PER_PAGE: usize = 30;
+/// Lines that are shown before and after the current line of synced lyrics
+const LINES_BEFORE: usize = 2;
+const LINES_AFTER: usize = 5;
+/// Lyrics of tracks that differ more in length are for another version of the song
+const DURATION_TOLERANCE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricLine {
+    /// Start of the line in synced lyrics
+    pub time: Option<Duration>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Lyrics {
+    pub lines: Vec<LyricLine>,
+}
+
+/// Parse timestamps like `01:23.45`
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (minutes, seconds) = timestamp.split_once(':')?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    (seconds >= 0.0).then(|| Duration::from_secs(minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+impl Lyrics {
+    /// Read lyrics in the LRC format, lines without timestamps are kept as plain lyrics
+    pub fn parse(text: &str) -> Self {
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            let mut rest = line.trim();
+            let mut times = Vec::new();
+            let mut has_tags = false;
+            while let Some((tag, text)) = rest
+                .strip_prefix('[')
+                .and_then(|tagged| tagged.split_once(']'))
+            {
+                has_tags = true;
+                times.extend(parse_timestamp(tag));
+                rest = text;
+            }
+            // Tags like `[ar: Artist]` describe the song and are no lyrics
+            if has_tags && times.is_empty() {
+                continue;
+            }
+            let text = rest.trim().to_string();
+            if times.is_empty() {
+                lines.push(LyricLine { time: None, text });
+            } else {
+                lines.extend(times.into_iter().map(|time| LyricLine {
+                    time: Some(time),
+                    text: text.clone(),
+                }));
+            }
+        }
+        // Repeated lines can share a single line with several timestamps
+        lines.sort_by_key(|line| line.time);
+        Self { lines }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.text.is_empty())
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.lines.iter().any(|line| line.time.is_some())
+    }
+
+    /// Index of the line that is sung at the position
+    pub fn current_line(&self, position: Duration) -> Option<usize> {
+        self.lines
+            .iter()
+            .rposition(|line| line.time.is_some_and(|time| time <= position))
+    }
+
+    /// Split the lyrics into pages that fit into an embed
+    pub fn pages(&self) -> Vec<String> {
+        let mut pages = Vec::new();
+        let mut page = String::new();
+        let mut page_lines = 0;
+        for line in &self.lines {
+            // Lines that don't fit into an embed at all are cut
+            let text: String = line.text.chars().take(MAX_PAGE_LENGTH - 1).collect();
+            let length = page.chars().count() + text.chars().count();
+            if page_lines == LINES_PER_PAGE || length >= MAX_PAGE_LENGTH {
+                if !page.trim().is_empty() {
+                    pages.push(std::mem::take(&mut page));
+                }
+                page.clear();
+                page_lines = 0;
+            }
+            page.push_str(&text);
+            page.push('\n');
+            page_lines += 1;
+        }
+        if !page.trim().is_empty() {
+            pages.push(page);
+        }
+        pages
+    }
+
+    /// The current line in bold, surrounded by the lines before and after it
+    pub fn synced_view(&self, position: Duration) -> String {
+        let current = self.current_line(position);
+        let start = current.map_or(0, |current| current.saturating_sub(LINES_BEFORE));
+        let end = current.map_or(LINES_AFTER, |current| current + LINES_AFTER + 1);
+        self.lines
+            .iter()
+            .enumerate()
+            .take(end)
+            .skip(start)
+            .filter(|(_, line)| !line.text.is_empty())
+            .map(|(index, line)| {
+                if Some(index) == current {
+                    format!("▶️ **{}**", line.text)
+                } else {
+                    line.text.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Song that lyrics are looked up for
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricsQuery {
+    pub title: String,
+    pub artist: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl LyricsQuery {
+    /// Video titles like `Artist - Title (Official Video)` contain the artist and some noise
+    pub fn from_metadata(metadata: &AuxMetadata) -> Option<Self> {
+        let title = metadata.title.as_deref()?;
+        let (artist, title) = match title.split_once(" - ") {
+            Some((artist, title)) => (Some(artist.to_string()), title),
+            None => (metadata.artist.clone(), title),
+        };
+        let title = title
+            .split(['(', '['])
+            .next()
+            .unwrap_or(title)
+            .trim()
+            .to_string();
+        (!title.is_empty()).then(|| Self {
+            title,
+            artist: artist.map(|artist| artist.trim_end_matches(" - Topic").to_string()),
+            duration: metadata.duration,
+        })
+    }
+}
+
+/// Looks up the lyrics of a song
+#[async_trait]
+pub trait LyricsProvider: Send + Sync {
+    async fn lyrics(&self, query: &LyricsQuery) -> Result<Option<Lyrics>, CadencyError>;
+}
+
+/// Looks up lyrics on LRCLIB, which prefers synced lyrics
+pub struct LrclibProvider {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl LrclibProvider {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_base_url(client, "https://lrclib.net")
+    }
+
+    /// Use another server, e.g. a local server with fixtures
+    pub fn with_base_url(client: reqwest::Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+/// Pick the lyrics of the first result with a matching duration
+fn pick_lrclib_result(results: &[Value], duration: Option<Duration>) -> Option<Lyrics> {
+    results
+        .iter()
+        .filter(|result| {
+            let result_duration = result
+                .get("duration")
+                .and_then(Value::as_f64)
+                .map(|duration| Duration::from_secs_f64(duration.max(0.0)));
+            match (duration, result_duration) {
+                (Some(duration), Some(result_duration)) => {
+                    duration.abs_diff(result_duration) <= DURATION_TOLERANCE
+                }
+                _ => true,
+            }
+        })
+        .find_map(|result| {
+            let text = ["syncedLyrics", "plainLyrics"]
+                .into_iter()
+                .find_map(|key| result.get(key).and_then(Value::as_str))?;
+            Some(Lyrics::parse(text)).filter(|lyrics| !lyrics.is_empty())
+        })
+}
+
+#[async_trait]
+impl LyricsProvider for LrclibProvider {
+    async fn lyrics(&self, query: &LyricsQuery) -> Result<Option<Lyrics>, CadencyError> {
+        let mut params = vec![("track_name", query.title.as_str())];
+        if let Some(artist) = &query.artist {
+            params.push(("artist_name", artist));
+        }
+        let url = format!("{}/api/search", self.url);
+        debug!("Look up lyrics of {query:?}");
+        let results: Vec<Value> = self
+            .client
+            .get(&url)
+            .query(&params)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| CadencyError::Runtime(format!("Failed to look up lyrics: {err}")))?
+            .json()
+            .await
+            .map_err(|err| CadencyError::Runtime(format!("Failed to read lyrics: {err}")))?;
+        Ok(pick_lrclib_result(&results, query.duration))
+    }
+}
+
+pub struct LyricsProviderKey;
+
+impl TypeMapKey for LyricsProviderKey {
+    type Value = Arc<dyn LyricsProvider>;
+}
+
+pub async fn get_lyrics_provider(ctx: &Context) -> Arc<dyn LyricsProvider> {
+    let data = ctx.data.read().await;
+    data.get::<LyricsProviderKey>()
+        .expect("Expected LyricsProviderKey in TypeMap.")
+        .clone()
+}
+
+/// Lyrics of a track, library tracks use the `.lrc` file next to the audio file if there is one
+pub async fn find_lyrics(
+    ctx: &Context,
+    track_data: &TrackData,
+) -> Result<Option<Lyrics>, CadencyError> {
+    let library_track = match track_data.url() {
+        Some(url) if url.starts_with("file:") => get_library(ctx).await.get_by_url(url),
+        _ => None,
+    };
+    let query = match library_track {
+        Some(library_track) => {
+            let lyrics_path = library_track.lyrics_path();
+            match std::fs::read_to_string(&lyrics_path) {
+                Ok(text) => {
+                    return Ok(Some(Lyrics::parse(&text)).filter(|lyrics| !lyrics.is_empty()))
+                }
+                Err(err) => debug!("No lyrics at {}: {err}", lyrics_path.display()),
+            }
+            // Tags of library files hold the artist and title separately
+            Some(LyricsQuery {
+                title: library_track.title,
+                artist: library_track.artist,
+                duration: library_track.duration,
+            })
+        }
+        None => LyricsQuery::from_metadata(&track_data.metadata),
+    };
+    match query {
+        Some(query) => get_lyrics_provider(ctx).await.lyrics(&query).await,
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    const LRC: &str = "[ar: The Beatles]\n[ti: Hey Jude]\n[00:07.50]Hey Jude, don't make it bad\n[00:12.00][01:30.00]Take a sad song\n[00:18.25]\n[00:20.00]Remember to let her into your heart";
+
+    #[test]
+    fn should_parse_lrc() {
+        let lyrics = Lyrics::parse(LRC);
+        assert!(lyrics.is_synced());
+        assert_eq!(lyrics.lines.len(), 5);
+        assert_eq!(lyrics.lines[0].time, Some(Duration::from_millis(7500)));
+        assert_eq!(lyrics.lines[4].text, "Take a sad song");
+        assert_eq!(lyrics.current_line(Duration::from_secs(5)), None);
+        assert_eq!(lyrics.current_line(Duration::from_secs(13)), Some(1));
+        assert_eq!(lyrics.current_line(Duration::from_secs(200)), Some(4));
+        assert!(!Lyrics::parse("Hey Jude\nDon't make it bad").is_synced());
+    }
+
+    #[test]
+    fn should_highlight_current_line() {
+        let view = Lyrics::parse(LRC).synced_view(Duration::from_secs(13));
+        assert_eq!(
+            view,
+            "Hey Jude, don't make it bad\n▶️ **Take a sad song**\nRemember to let her into your heart\nTake a sad song"
+        );
+    }
+
+    #[test]
+    fn should_split_long_lyrics_into_pages() {
+        let text = (0..70)
+            .map(|line| format!("Line {line}\n"))
+            .collect::<String>();
+        let pages = Lyrics::parse(&text).pages();
+        assert_eq!(pages.len(), 3);
+        assert!(pages[0].starts_with("Line 0\n"));
+        assert!(pages[2].starts_with("Line 60\n"));
+    }
+
+    #[test]
+    fn should_not_create_blank_pages() {
+        let long_line = "a".repeat(5000);
+        let text = format!("{long_line}\n{}\nLast line", "\n".repeat(40));
+        let pages = Lyrics::parse(&text).pages();
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|page| !page.trim().is_empty()));
+        assert!(pages[0].chars().count() <= MAX_PAGE_LENGTH);
+        assert!(pages[1].contains("Last line"));
+    }
+
+    #[test]
+    fn should_clean_up_video_titles() {
+        let metadata = AuxMetadata {
+            title: Some("The Beatles - Hey Jude (Official Video) [4K]".to_string()),
+            artist: Some("TheBeatlesVEVO".to_string()),
+            ..Default::default()
+        };
+        let query = LyricsQuery::from_metadata(&metadata).unwrap();
+        assert_eq!(query.title, "Hey Jude");
+        assert_eq!(query.artist.as_deref(), Some("The Beatles"));
+        let metadata = AuxMetadata {
+            title: Some("Hey Jude".to_string()),
+            artist: Some("The Beatles - Topic".to_string()),
+            ..Default::default()
+        };
+        let query = LyricsQuery::from_metadata(&metadata).unwrap();
+        assert_eq!(query.artist.as_deref(), Some("The Beatles"));
+    }
+
+    #[test]
+    fn should_pick_result_with_matching_duration() {
+        let results = vec![
+            json!({"duration": 500.0, "syncedLyrics": "[00:01.00]Live version"}),
+            json!({"duration": 431.0, "syncedLyrics": null, "plainLyrics": "Hey Jude"}),
+        ];
+        let lyrics = pick_lrclib_result(&results, Some(Duration::from_secs(430))).unwrap();
+        assert_eq!(lyrics.lines[0].text, "Hey Jude");
+        assert!(pick_lrclib_result(&results[..1], Some(Duration::from_secs(430))).is_none());
+    }
+}
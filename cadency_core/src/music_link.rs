@@ -0,0 +1,428 @@
+use crate::error::CadencyError;
+use serde_json::Value;
+use serenity::{async_trait, client::Context, prelude::TypeMapKey};
+use std::{sync::Arc, time::Duration};
+
+/// Streaming services whose links are played by searching their tracks on YouTube
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Spotify,
+    AppleMusic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Track,
+    Album,
+    Playlist,
+}
+
+impl LinkKind {
+    fn path(&self) -> &'static str {
+        match self {
+            LinkKind::Track => "track",
+            LinkKind::Album => "album",
+            LinkKind::Playlist => "playlist",
+        }
+    }
+}
+
+/// Link to a track, an album or a playlist of a streaming service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MusicLink {
+    pub service: Service,
+    pub kind: LinkKind,
+    pub id: String,
+    /// Apple Music links belong to the store of a country
+    pub country: Option<String>,
+}
+
+impl MusicLink {
+    /// Recognise links like `https://open.spotify.com/track/<id>` or
+    /// `https://music.apple.com/us/album/<name>/<id>?i=<track id>`
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match url.host_str()? {
+            "open.spotify.com" | "play.spotify.com" => {
+                // Localised links start with a segment like `intl-de`
+                let segments = match segments.first() {
+                    Some(segment) if segment.starts_with("intl-") => &segments[1..],
+                    _ => &segments[..],
+                };
+                let kind = match *segments.first()? {
+                    "track" => LinkKind::Track,
+                    "album" => LinkKind::Album,
+                    "playlist" => LinkKind::Playlist,
+                    _ => return None,
+                };
+                Some(Self {
+                    service: Service::Spotify,
+                    kind,
+                    id: segments.get(1)?.to_string(),
+                    country: None,
+                })
+            }
+            "music.apple.com" => {
+                let country = segments.first()?.to_string();
+                let id = segments.last()?.to_string();
+                // A track of an album is selected by the `i` parameter
+                let track_id = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "i")
+                    .map(|(_, value)| value.to_string());
+                let (kind, id) = match (*segments.get(1)?, track_id) {
+                    ("song", _) => (LinkKind::Track, id),
+                    ("album", Some(track_id)) => (LinkKind::Track, track_id),
+                    ("album", None) => (LinkKind::Album, id),
+                    ("playlist", _) => (LinkKind::Playlist, id),
+                    _ => return None,
+                };
+                Some(Self {
+                    service: Service::AppleMusic,
+                    kind,
+                    id,
+                    country: Some(country),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Track of a streaming service that is searched on YouTube
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedTrack {
+    pub title: String,
+    pub artists: Vec<String>,
+    pub duration: Option<Duration>,
+}
+
+impl LinkedTrack {
+    /// Query that finds the track on YouTube
+    pub fn search_query(&self) -> String {
+        if self.artists.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} - {}", self.artists.join(", "), self.title)
+        }
+    }
+}
+
+/// Looks up the tracks behind a link of a streaming service
+#[async_trait]
+pub trait MusicLinkProvider: Send + Sync {
+    async fn tracks(&self, link: &MusicLink) -> Result<Vec<LinkedTrack>, CadencyError>;
+}
+
+/// Reads the tracks from the public embed pages of Spotify and the pages of Apple Music
+pub struct EmbedMetadataProvider {
+    client: reqwest::Client,
+    spotify_url: String,
+    apple_music_url: String,
+}
+
+impl EmbedMetadataProvider {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_base_urls(
+            client,
+            "https://open.spotify.com",
+            "https://music.apple.com",
+        )
+    }
+
+    /// Use other servers for the pages, e.g. a local server with fixtures
+    pub fn with_base_urls(
+        client: reqwest::Client,
+        spotify_url: &str,
+        apple_music_url: &str,
+    ) -> Self {
+        Self {
+            client,
+            spotify_url: spotify_url.trim_end_matches('/').to_string(),
+            apple_music_url: apple_music_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn page_url(&self, link: &MusicLink) -> String {
+        match link.service {
+            Service::Spotify => {
+                format!(
+                    "{}/embed/{}/{}",
+                    self.spotify_url,
+                    link.kind.path(),
+                    link.id
+                )
+            }
+            Service::AppleMusic => {
+                let path = match link.kind {
+                    LinkKind::Track => "song",
+                    kind => kind.path(),
+                };
+                format!(
+                    "{}/{}/{path}/{}",
+                    self.apple_music_url,
+                    link.country.as_deref().unwrap_or("us"),
+                    link.id
+                )
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MusicLinkProvider for EmbedMetadataProvider {
+    async fn tracks(&self, link: &MusicLink) -> Result<Vec<LinkedTrack>, CadencyError> {
+        let url = self.page_url(link);
+        debug!("Look up the tracks of {link:?} at '{url}'");
+        let page = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| CadencyError::Runtime(format!("Failed to load '{url}': {err}")))?
+            .text()
+            .await
+            .map_err(|err| CadencyError::Runtime(format!("Failed to read '{url}': {err}")))?;
+        Ok(match link.service {
+            Service::Spotify => parse_spotify_embed(&page),
+            Service::AppleMusic => parse_apple_music_page(&page),
+        })
+    }
+}
+
+pub struct MusicLinkProviderKey;
+
+impl TypeMapKey for MusicLinkProviderKey {
+    type Value = Arc<dyn MusicLinkProvider>;
+}
+
+pub async fn get_music_link_provider(ctx: &Context) -> Arc<dyn MusicLinkProvider> {
+    let data = ctx.data.read().await;
+    data.get::<MusicLinkProviderKey>()
+        .expect("Expected MusicLinkProviderKey in TypeMap.")
+        .clone()
+}
+
+/// Contents of the script tags whose opening tag contains the marker
+fn script_contents<'a>(html: &'a str, marker: &str) -> Vec<&'a str> {
+    html.split("<script")
+        .skip(1)
+        .filter_map(|script| {
+            let (tag, rest) = script.split_once('>')?;
+            let (content, _) = rest.split_once("</script>")?;
+            tag.contains(marker).then_some(content)
+        })
+        .collect()
+}
+
+fn names(value: &Value) -> Vec<String> {
+    let name = |value: &Value| value.get("name").and_then(Value::as_str).map(str::to_owned);
+    match value {
+        Value::Array(values) => values.iter().filter_map(name).collect(),
+        value => name(value).into_iter().collect(),
+    }
+}
+
+/// The embed page of Spotify contains the tracks in its Next.js data
+fn parse_spotify_embed(html: &str) -> Vec<LinkedTrack> {
+    let Some(entity) = script_contents(html, "__NEXT_DATA__")
+        .into_iter()
+        .filter_map(|content| serde_json::from_str::<Value>(content).ok())
+        .find_map(|data| data.pointer("/props/pageProps/state/data/entity").cloned())
+    else {
+        return Vec::new();
+    };
+    let duration = |value: &Value| value.as_u64().map(Duration::from_millis);
+    match entity.get("trackList").and_then(Value::as_array) {
+        Some(track_list) => track_list
+            .iter()
+            .filter_map(|track| {
+                Some(LinkedTrack {
+                    title: track.get("title")?.as_str()?.to_string(),
+                    artists: track
+                        .get("subtitle")
+                        .and_then(Value::as_str)
+                        .map(|artists| artists.split(", ").map(str::to_owned).collect())
+                        .unwrap_or_default(),
+                    duration: track.get("duration").and_then(duration),
+                })
+            })
+            .collect(),
+        None => entity
+            .get("name")
+            .and_then(Value::as_str)
+            .map(|title| LinkedTrack {
+                title: title.to_string(),
+                artists: entity.get("artists").map(names).unwrap_or_default(),
+                duration: entity.get("duration").and_then(duration),
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Parse durations like `PT3M45S`
+fn parse_iso_duration(duration: &str) -> Option<Duration> {
+    let mut seconds = 0;
+    let mut number = String::new();
+    for character in duration.strip_prefix("PT")?.chars() {
+        match character {
+            '0'..='9' => number.push(character),
+            'H' | 'M' | 'S' => {
+                let value: u64 = number.parse().ok()?;
+                number.clear();
+                seconds += match character {
+                    'H' => value * 3600,
+                    'M' => value * 60,
+                    _ => value,
+                };
+            }
+            _ => return None,
+        }
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+fn recording(value: &Value, artists: &[String]) -> Option<LinkedTrack> {
+    let track_artists = value.get("byArtist").map(names).unwrap_or_default();
+    Some(LinkedTrack {
+        title: value.get("name")?.as_str()?.to_string(),
+        artists: if track_artists.is_empty() {
+            artists.to_vec()
+        } else {
+            track_artists
+        },
+        duration: value
+            .get("duration")
+            .and_then(Value::as_str)
+            .and_then(parse_iso_duration),
+    })
+}
+
+/// The pages of Apple Music describe songs, albums and playlists with schema.org data
+fn parse_apple_music_page(html: &str) -> Vec<LinkedTrack> {
+    script_contents(html, "application/ld+json")
+        .into_iter()
+        .filter_map(|content| serde_json::from_str::<Value>(content).ok())
+        .find_map(|data| {
+            let artists = data.get("byArtist").map(names).unwrap_or_default();
+            match data.get("@type").and_then(Value::as_str)? {
+                "MusicRecording" | "MusicComposition" => {
+                    Some(recording(&data, &[]).into_iter().collect())
+                }
+                "MusicAlbum" | "MusicPlaylist" => {
+                    let tracks = data.get("tracks").or_else(|| data.get("track"))?;
+                    Some(
+                        tracks
+                            .as_array()?
+                            .iter()
+                            .filter_map(|track| recording(track, &artists))
+                            .collect(),
+                    )
+                }
+                _ => None,
+            }
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const SPOTIFY_ALBUM: &str = r#"<html><body><script id="__NEXT_DATA__" type="application/json">{"props":{"pageProps":{"state":{"data":{"entity":{"type":"album","name":"Abbey Road","trackList":[{"title":"Come Together","subtitle":"The Beatles","duration":259946},{"title":"Something","subtitle":"The Beatles","duration":182293}]}}}}}}</script></body></html>"#;
+    const SPOTIFY_TRACK: &str = r#"<script id="__NEXT_DATA__" type="application/json">{"props":{"pageProps":{"state":{"data":{"entity":{"type":"track","name":"Hey Jude","artists":[{"name":"The Beatles"}],"duration":431000}}}}}}</script>"#;
+    const APPLE_MUSIC_ALBUM: &str = r#"<script type="application/ld+json" id="schema:music-album">{"@context":"http://schema.org","@type":"MusicAlbum","name":"Abbey Road","byArtist":[{"@type":"MusicGroup","name":"The Beatles"}],"tracks":[{"@type":"MusicRecording","name":"Come Together","duration":"PT4M20S"},{"@type":"MusicRecording","name":"Something","duration":"PT3M3S"}]}</script>"#;
+
+    #[test]
+    fn should_parse_music_links() {
+        assert_eq!(
+            MusicLink::parse("https://open.spotify.com/intl-de/track/4uLU6hMCjMI75M1A2tKUQC?si=1"),
+            Some(MusicLink {
+                service: Service::Spotify,
+                kind: LinkKind::Track,
+                id: "4uLU6hMCjMI75M1A2tKUQC".to_string(),
+                country: None,
+            })
+        );
+        let link =
+            MusicLink::parse("https://music.apple.com/us/album/abbey-road/1441164426?i=1441164430")
+                .unwrap();
+        assert_eq!(
+            (link.kind, link.id.as_str()),
+            (LinkKind::Track, "1441164430")
+        );
+        let link = MusicLink::parse(
+            "https://music.apple.com/de/playlist/chill/pl.2b0e6e332fdf4b7a91164da3162127b5",
+        )
+        .unwrap();
+        assert_eq!(link.kind, LinkKind::Playlist);
+        assert_eq!(link.country.as_deref(), Some("de"));
+        assert_eq!(
+            MusicLink::parse("https://www.youtube.com/watch?v=A_MjCqQoLLA"),
+            None
+        );
+        assert_eq!(
+            MusicLink::parse("https://open.spotify.com/artist/3WrFJ7ztbogyGnTHbHJFl2"),
+            None
+        );
+    }
+
+    #[test]
+    fn should_read_spotify_embeds() {
+        let tracks = parse_spotify_embed(SPOTIFY_ALBUM);
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].search_query(), "The Beatles - Come Together");
+        assert_eq!(tracks[1].duration, Some(Duration::from_millis(182293)));
+        let tracks = parse_spotify_embed(SPOTIFY_TRACK);
+        assert_eq!(tracks[0].search_query(), "The Beatles - Hey Jude");
+        assert!(parse_spotify_embed("<html></html>").is_empty());
+    }
+
+    #[test]
+    fn should_read_apple_music_pages() {
+        let tracks = parse_apple_music_page(APPLE_MUSIC_ALBUM);
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].search_query(), "The Beatles - Come Together");
+        assert_eq!(tracks[1].duration, Some(Duration::from_secs(183)));
+        assert_eq!(
+            parse_iso_duration("PT1H2M3S"),
+            Some(Duration::from_secs(3723))
+        );
+    }
+
+    /// Serves a single page like the streaming service would
+    async fn serve_page(page: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+                page.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn should_resolve_links_with_local_stand_in() {
+        let spotify_url = serve_page(SPOTIFY_ALBUM).await;
+        let provider =
+            EmbedMetadataProvider::with_base_urls(reqwest::Client::new(), &spotify_url, "");
+        let link =
+            MusicLink::parse("https://open.spotify.com/album/0ETFjACtuP2ADo6LFhL6HN").unwrap();
+        let tracks = provider.tracks(&link).await.unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].title, "Come Together");
+    }
+}
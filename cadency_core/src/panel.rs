@@ -0,0 +1,307 @@
+use crate::{
+    error::CadencyError,
+    filter::FilterPreset,
+    guild::get_guild_states,
+    track::TrackData,
+    utils::{
+        self,
+        controls::{self, LoopMode},
+        policy::Controller,
+    },
+};
+use serenity::{
+    all::Mentionable,
+    builder::{
+        CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse,
+        CreateInteractionResponseMessage, CreateMessage, EditMessage,
+    },
+    client::Context,
+    model::{
+        application::{ButtonStyle, ComponentInteraction},
+        colour::Colour,
+        id::GuildId,
+    },
+};
+use songbird::tracks::{LoopState, PlayMode};
+
+/// Custom ids of the panel buttons start with this prefix
+pub const CUSTOM_ID_PREFIX: &str = "panel:";
+
+/// Buttons of the now playing panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PanelButton {
+    PauseResume,
+    Skip,
+    Loop,
+    Shuffle,
+    Stop,
+}
+
+impl PanelButton {
+    const ALL: [PanelButton; 5] = [
+        PanelButton::PauseResume,
+        PanelButton::Skip,
+        PanelButton::Loop,
+        PanelButton::Shuffle,
+        PanelButton::Stop,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            PanelButton::PauseResume => "pause",
+            PanelButton::Skip => "skip",
+            PanelButton::Loop => "loop",
+            PanelButton::Shuffle => "shuffle",
+            PanelButton::Stop => "stop",
+        }
+    }
+
+    fn custom_id(&self) -> String {
+        format!("{CUSTOM_ID_PREFIX}{}", self.name())
+    }
+
+    fn from_custom_id(custom_id: &str) -> Option<Self> {
+        let name = custom_id.strip_prefix(CUSTOM_ID_PREFIX)?;
+        Self::ALL.into_iter().find(|button| button.name() == name)
+    }
+}
+
+/// Playback state of the current track that is shown in the panel
+struct Playing {
+    description: String,
+    is_paused: bool,
+    is_looping: bool,
+}
+
+/// Description of the current track, shared by `/now` and the panel
+pub fn now_playing_description(
+    track_data: &TrackData,
+    loop_state: LoopState,
+    filter: FilterPreset,
+) -> String {
+    let title = track_data.title().unwrap_or("Unknown Song");
+    // Library files have a local url that can't be opened in Discord
+    let url = track_data.url().filter(|url| url.starts_with("http"));
+
+    let mut description = format!("🎵 **Now Playing:** `{}`", title);
+    if let Some(live) = &track_data.live {
+        description.push_str("\n🔴 **Live**");
+        if let Some(stream_title) = live.title() {
+            description.push_str(&format!("\n📻 **On Air:** {}", stream_title));
+        }
+    }
+    if let Some(url) = url {
+        description.push_str(&format!("\n🔗 **Link:** [View Source]({})", url));
+    }
+    if let Some(playlist) = &track_data.playlist {
+        description.push_str(&format!("\n📋 **Playlist:** [View Playlist]({})", playlist));
+    }
+    description.push_str(&format!(
+        "\n👤 **Requested by:** {} <t:{}:R>",
+        track_data.requester.mention(),
+        track_data.requested_at.unix_timestamp()
+    ));
+    match loop_state {
+        LoopState::Infinite => {
+            description.push_str("\n🔁 **Loop:** Infinite");
+        }
+        LoopState::Finite(count) if count > 0 => {
+            description.push_str(&format!("\n🔁 **Loop:** {} times remaining", count));
+        }
+        _ => {}
+    }
+    if filter != FilterPreset::None {
+        description.push_str(&format!("\n🎛️ **Filter:** {}", filter));
+    }
+    description
+}
+
+async fn playing(ctx: &Context, guild_id: GuildId) -> Option<Playing> {
+    let call = utils::voice::get_songbird(ctx).await.get(guild_id)?;
+    let handler = call.lock().await;
+    let track = handler.queue().current()?;
+    // The queue still holds the last track for a moment after it ended
+    let info = track.get_info().await.ok()?;
+    if info.playing.is_done() {
+        return None;
+    }
+    let filter = get_guild_states(ctx)
+        .await
+        .get(guild_id, |state| state.settings.filter);
+    let mut description = now_playing_description(&track.data::<TrackData>(), info.loops, filter);
+    let upcoming = handler.queue().current_queue();
+    if let Some(next) = upcoming.get(1) {
+        description.push_str(&format!(
+            "\n\n⏭️ **Up Next:** `{}`",
+            next.data::<TrackData>().title().unwrap_or("Unknown Song")
+        ));
+        if upcoming.len() > 2 {
+            description.push_str(&format!(" and {} more", upcoming.len() - 2));
+        }
+    }
+    Some(Playing {
+        description,
+        is_paused: info.playing == PlayMode::Pause,
+        is_looping: matches!(info.loops, LoopState::Infinite | LoopState::Finite(1..)),
+    })
+}
+
+fn buttons(playing: Option<&Playing>) -> Vec<CreateActionRow> {
+    let is_idle = playing.is_none();
+    let is_paused = playing.is_some_and(|playing| playing.is_paused);
+    let is_looping = playing.is_some_and(|playing| playing.is_looping);
+    let buttons = PanelButton::ALL
+        .into_iter()
+        .map(|button| {
+            let (emoji, label, style) = match button {
+                PanelButton::PauseResume if is_paused => ('▶', "Resume", ButtonStyle::Success),
+                PanelButton::PauseResume => ('⏸', "Pause", ButtonStyle::Secondary),
+                PanelButton::Skip => ('⏭', "Skip", ButtonStyle::Secondary),
+                PanelButton::Loop if is_looping => ('🔁', "Loop", ButtonStyle::Success),
+                PanelButton::Loop => ('🔁', "Loop", ButtonStyle::Secondary),
+                PanelButton::Shuffle => ('🔀', "Shuffle", ButtonStyle::Secondary),
+                PanelButton::Stop => ('⏹', "Stop", ButtonStyle::Danger),
+            };
+            CreateButton::new(button.custom_id())
+                .emoji(emoji)
+                .label(label)
+                .style(style)
+                .disabled(is_idle)
+        })
+        .collect();
+    vec![CreateActionRow::Buttons(buttons)]
+}
+
+async fn render(ctx: &Context, guild_id: GuildId) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let playing = playing(ctx, guild_id).await;
+    let embed = CreateEmbed::default()
+        .title("🎧 Now Playing")
+        .color(Colour::from_rgb(255, 110, 64)); // Coral
+    let embed = match &playing {
+        Some(playing) if playing.is_paused => {
+            embed.description(format!("⏸️ **Paused**\n\n{}", playing.description))
+        }
+        Some(playing) => embed.description(&playing.description),
+        None => embed.description("💤 **Nothing is playing**\n\nUse `/play` to add a song."),
+    };
+    (embed, buttons(playing.as_ref()))
+}
+
+/// Update the now playing panel of the guild if it is enabled.
+/// The panel is posted again when the music moved to another text channel or it was deleted.
+pub async fn refresh(ctx: &Context, guild_id: GuildId) {
+    let guild_states = get_guild_states(ctx).await;
+    let (enabled, text_channel, panel_message) = guild_states.get(guild_id, |state| {
+        (
+            state.settings.now_playing_panel,
+            state.text_channel,
+            state.panel_message,
+        )
+    });
+    let Some(text_channel) = text_channel.filter(|_| enabled) else {
+        return;
+    };
+    let (embed, components) = render(ctx, guild_id).await;
+    match panel_message {
+        Some((channel_id, message_id)) if channel_id == text_channel => {
+            let edit = EditMessage::new()
+                .embed(embed.clone())
+                .components(components.clone());
+            match channel_id.edit_message(&ctx.http, message_id, edit).await {
+                Ok(_) => return,
+                Err(err) => debug!("Panel can't be edited, post it again: {err}"),
+            }
+        }
+        Some(_) => remove(ctx, guild_id).await,
+        None => {}
+    }
+    let message = CreateMessage::new().embed(embed).components(components);
+    match text_channel.send_message(&ctx.http, message).await {
+        Ok(message) => guild_states.update(guild_id, |state| {
+            state.panel_message = Some((message.channel_id, message.id))
+        }),
+        Err(err) => error!("❌ Failed to post the now playing panel: {err}"),
+    }
+}
+
+/// Delete the now playing panel of the guild
+pub async fn remove(ctx: &Context, guild_id: GuildId) {
+    let panel_message = get_guild_states(ctx)
+        .await
+        .update(guild_id, |state| state.panel_message.take());
+    if let Some((channel_id, message_id)) = panel_message {
+        if let Err(err) = channel_id.delete_message(&ctx.http, message_id).await {
+            debug!("Failed to delete the now playing panel: {err}");
+        }
+    }
+}
+
+async fn press(
+    ctx: &Context,
+    controller: &Controller<'_>,
+    button: PanelButton,
+) -> Result<CreateEmbed, CadencyError> {
+    let playing = playing(ctx, controller.guild_id).await;
+    let is_paused = playing.as_ref().is_some_and(|playing| playing.is_paused);
+    let is_looping = playing.as_ref().is_some_and(|playing| playing.is_looping);
+    match button {
+        PanelButton::PauseResume if is_paused => controls::resume(ctx, controller).await,
+        PanelButton::PauseResume => controls::pause(ctx, controller).await,
+        PanelButton::Skip => controls::skip(ctx, controller).await,
+        PanelButton::Loop if is_looping => controls::set_loop(ctx, controller, LoopMode::Off).await,
+        PanelButton::Loop => controls::set_loop(ctx, controller, LoopMode::Infinite).await,
+        PanelButton::Shuffle => controls::shuffle(ctx, controller).await,
+        PanelButton::Stop => controls::stop(ctx, controller).await,
+    }
+}
+
+/// Run the action of a pressed panel button, the result is only shown to the user that pressed it
+pub async fn handle_interaction(ctx: &Context, interaction: &ComponentInteraction) {
+    let Some(button) = PanelButton::from_custom_id(&interaction.data.custom_id) else {
+        return;
+    };
+    debug!("⚡ Press '{}' button of the panel", button.name());
+    let result = match Controller::from_component(interaction) {
+        Ok(controller) => press(ctx, &controller, button).await,
+        Err(err) => Err(err),
+    };
+    let response = match result {
+        Ok(embed) => CreateInteractionResponseMessage::new().embed(embed),
+        Err(CadencyError::Command { message }) => {
+            CreateInteractionResponseMessage::new().content(message)
+        }
+        Err(err) => {
+            error!("❌ Panel button failed: {err:?}");
+            CreateInteractionResponseMessage::new()
+                .content("**Oops! Something went terrible wrong.**")
+        }
+    }
+    .ephemeral(true);
+    if let Err(err) = interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+        .await
+    {
+        error!("❌ Failed to respond to the panel button: {err}");
+    }
+    // Looping and shuffling don't cause track events
+    if let Some(guild_id) = interaction.guild_id {
+        refresh(ctx, guild_id).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_parse_button_custom_ids() {
+        for button in PanelButton::ALL {
+            assert_eq!(
+                PanelButton::from_custom_id(&button.custom_id()),
+                Some(button)
+            );
+        }
+        assert_eq!(PanelButton::from_custom_id("search_pick"), None);
+        assert_eq!(PanelButton::from_custom_id("panel:unknown"), None);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    error::CadencyError,
+    guild::{get_guild_states, GuildStates},
+    handler::voice::register_voice_handlers,
+    utils::{self, queue::QueueSnapshot},
+};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    builder::{CreateEmbed, CreateMessage},
+    client::Context,
+    model::{
+        colour::Colour,
+        gateway::ShardInfo,
+        id::{ChannelId, GuildId},
+    },
+    prelude::TypeMapKey,
+};
+use songbird::Songbird;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+/// File where the queues are saved on shutdown, no queues are saved if not set
+pub(crate) struct QueueFile;
+
+impl TypeMapKey for QueueFile {
+    type Value = Option<PathBuf>;
+}
+
+/// Saved queues that wait for the shard of their guild, `None` until the file was read
+pub(crate) struct PendingQueues;
+
+impl TypeMapKey for PendingQueues {
+    type Value = Arc<Mutex<Option<Vec<SavedQueue>>>>;
+}
+
+/// Queue of a guild that is resumed after a restart
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedQueue {
+    pub guild_id: GuildId,
+    pub voice_channel: ChannelId,
+    pub text_channel: Option<ChannelId>,
+    pub snapshot: QueueSnapshot,
+}
+
+/// Collect the queues of all guilds the bot is playing music in
+pub async fn collect_queues(manager: &Songbird, guild_states: &GuildStates) -> Vec<SavedQueue> {
+    let mut saved_queues = Vec::new();
+    for (guild_id, call) in manager.iter() {
+        let guild_id = GuildId::new(guild_id.0.get());
+        let handler = call.lock().await;
+        let Some(voice_channel) = handler.current_channel() else {
+            continue;
+        };
+        // Uploaded files can't be played anymore after the restart
+        let snapshot = utils::queue::snapshot_queue(handler.queue())
+            .await
+            .without_expiring();
+        if snapshot.tracks.is_empty() {
+            continue;
+        }
+        saved_queues.push(SavedQueue {
+            guild_id,
+            voice_channel: ChannelId::new(voice_channel.0.get()),
+            text_channel: guild_states.get(guild_id, |state| state.text_channel),
+            snapshot,
+        });
+    }
+    saved_queues
+}
+
+/// Write the queues of all guilds to the file
+pub async fn save_queues(
+    manager: &Songbird,
+    guild_states: &GuildStates,
+    path: &Path,
+) -> Result<usize, CadencyError> {
+    let saved_queues = collect_queues(manager, guild_states).await;
+    let json = serde_json::to_string_pretty(&saved_queues)?;
+    tokio::fs::write(path, json).await?;
+    Ok(saved_queues.len())
+}
+
+/// Read the saved queues and remove the file, so they are only resumed once
+async fn take_saved_queues(path: &Path) -> Result<Vec<SavedQueue>, CadencyError> {
+    let json = match tokio::fs::read_to_string(path).await {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    tokio::fs::remove_file(path).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Whether the guild is handled by the shard, a bot without shards handles every guild
+fn is_on_shard(guild_id: GuildId, shard: Option<ShardInfo>) -> bool {
+    shard.is_none_or(|shard| serenity::utils::shard_id(guild_id, shard.total) == shard.id.0)
+}
+
+/// Take the saved queues of the guilds on the shard, the file is only read by the first shard
+async fn take_shard_queues(ctx: &Context, shard: Option<ShardInfo>) -> Vec<SavedQueue> {
+    let (queue_file, pending_queues) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<QueueFile>().cloned().flatten(),
+            data.get::<PendingQueues>().cloned(),
+        )
+    };
+    let (Some(queue_file), Some(pending_queues)) = (queue_file, pending_queues) else {
+        return Vec::new();
+    };
+    let mut pending_queues = pending_queues.lock().await;
+    let saved_queues = match pending_queues.take() {
+        Some(saved_queues) => saved_queues,
+        None => take_saved_queues(&queue_file).await.unwrap_or_else(|err| {
+            error!("❌ Failed to read saved queues: {err}");
+            Vec::new()
+        }),
+    };
+    let (shard_queues, other_queues) = saved_queues
+        .into_iter()
+        .partition(|saved_queue| is_on_shard(saved_queue.guild_id, shard));
+    *pending_queues = Some(other_queues);
+    shard_queues
+}
+
+/// Join the voice channels of the saved queues again and continue the playback.
+/// Every queue is only resumed once, even if the shard becomes ready again.
+pub(crate) async fn resume_queues(ctx: &Context, shard: Option<ShardInfo>) {
+    let saved_queues = take_shard_queues(ctx, shard).await;
+    let manager = utils::voice::get_songbird(ctx).await;
+    let guild_states = get_guild_states(ctx).await;
+    for saved_queue in saved_queues {
+        let guild_id = saved_queue.guild_id;
+        let call = match manager.join(guild_id, saved_queue.voice_channel).await {
+            Ok(call) => call,
+            Err(err) => {
+                error!("❌ Failed to rejoin voice channel in guild {guild_id}: {err:?}");
+                continue;
+            }
+        };
+        register_voice_handlers(ctx, &mut *call.lock().await, guild_id).await;
+        guild_states.update(guild_id, |state| {
+            state.text_channel = saved_queue.text_channel
+        });
+        let restored = utils::queue::restore_queue(ctx, call, guild_id, saved_queue.snapshot).await;
+        info!("▶️ Resumed {restored} tracks in guild {guild_id}");
+
+        if let Some(text_channel) = saved_queue.text_channel {
+            let embed = CreateEmbed::default()
+                .title("▶️ Resumed")
+                .color(Colour::from_rgb(0, 255, 127)) // Spring green
+                .description(format!(
+                    "✅ **Playback resumed after a restart**\n\n**{restored}** tracks are back in the queue."
+                ));
+            if let Err(err) = text_channel
+                .send_message(&ctx.http, CreateMessage::new().embed(embed))
+                .await
+            {
+                error!("Failed to send resume message: {err:?}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::track::QueuedTrack;
+    use serenity::model::id::UserId;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_take_saved_queues_once() {
+        let path = std::env::temp_dir().join(format!(
+            "cadency_saved_queues_test_{}.json",
+            std::process::id()
+        ));
+        let saved_queues = vec![SavedQueue {
+            guild_id: GuildId::new(1),
+            voice_channel: ChannelId::new(2),
+            text_channel: Some(ChannelId::new(3)),
+            snapshot: QueueSnapshot {
+                tracks: vec![QueuedTrack {
+                    title: Some("Title".to_string()),
+                    url: Some("https://example.com".to_string()),
+                    requester: UserId::new(4),
+                    playlist: None,
+                    duration: None,
+                    verified: false,
+                }],
+                position: Duration::from_secs(42),
+            },
+        }];
+        std::fs::write(&path, serde_json::to_string(&saved_queues).unwrap()).unwrap();
+
+        let taken = take_saved_queues(&path).await.unwrap();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].voice_channel, ChannelId::new(2));
+        assert_eq!(taken[0].snapshot.position, Duration::from_secs(42));
+        assert_eq!(taken[0].snapshot.tracks[0].requester, UserId::new(4));
+        assert!(take_saved_queues(&path).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_resume_guild_on_its_shard_only() {
+        let guild_id = GuildId::new(81384788765712384);
+        let shard = |id| {
+            Some(ShardInfo {
+                id: serenity::model::id::ShardId(id),
+                total: 2,
+            })
+        };
+        assert!(is_on_shard(guild_id, None));
+        assert_eq!(
+            is_on_shard(guild_id, shard(0)),
+            serenity::utils::shard_id(guild_id, 2) == 0
+        );
+        assert_ne!(
+            is_on_shard(guild_id, shard(0)),
+            is_on_shard(guild_id, shard(1))
+        );
+    }
+}
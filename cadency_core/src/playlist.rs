@@ -0,0 +1,245 @@
+use crate::{error::CadencyError, track::QueuedTrack};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::Context,
+    model::id::{GuildId, UserId},
+    prelude::TypeMapKey,
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+use tokio::sync::Mutex;
+
+/// Named list of tracks that a user saved on a server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPlaylist {
+    pub name: String,
+    pub owner: UserId,
+    pub guild_id: GuildId,
+    /// Other users of the server can load shared playlists
+    pub shared: bool,
+    pub tracks: Vec<QueuedTrack>,
+}
+
+impl SavedPlaylist {
+    fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    fn is_visible_to(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        self.guild_id == guild_id && (self.owner == user_id || self.shared)
+    }
+}
+
+/// Saved playlists of all users.
+/// The playlists are written to a json file after every change if a file is configured.
+#[derive(Debug, Default, Clone)]
+pub struct PlaylistStore {
+    path: Option<PathBuf>,
+    playlists: Arc<RwLock<Vec<SavedPlaylist>>>,
+    /// Only one change is written to the file at a time
+    file_lock: Arc<Mutex<()>>,
+}
+
+impl TypeMapKey for PlaylistStore {
+    type Value = PlaylistStore;
+}
+
+impl PlaylistStore {
+    /// Read the playlists from the file, a missing file is handled as no playlists
+    pub fn load(path: Option<PathBuf>) -> Result<Self, CadencyError> {
+        let playlists = match &path {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(json) => serde_json::from_str(&json)?,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(err) => return Err(err.into()),
+            },
+            None => Vec::new(),
+        };
+        Ok(Self {
+            path,
+            playlists: Arc::new(RwLock::new(playlists)),
+            file_lock: Arc::default(),
+        })
+    }
+
+    /// Find a playlist of the user or a playlist that is shared on the server.
+    /// Own playlists are preferred over shared playlists with the same name.
+    pub fn get(&self, guild_id: GuildId, user_id: UserId, name: &str) -> Option<SavedPlaylist> {
+        let playlists = self.playlists.read().expect("Playlists lock poisoned");
+        let matching = playlists.iter().filter(|playlist| {
+            playlist.is_named(name) && playlist.is_visible_to(guild_id, user_id)
+        });
+        matching
+            .clone()
+            .find(|playlist| playlist.owner == user_id)
+            .or_else(|| matching.clone().next())
+            .cloned()
+    }
+
+    /// All playlists of the user and the shared playlists of the server
+    pub fn list(&self, guild_id: GuildId, user_id: UserId) -> Vec<SavedPlaylist> {
+        let playlists = self.playlists.read().expect("Playlists lock poisoned");
+        playlists
+            .iter()
+            .filter(|playlist| playlist.is_visible_to(guild_id, user_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Save the playlist, replacing an existing playlist of the owner with the same name
+    pub async fn save(&self, playlist: SavedPlaylist) -> Result<(), CadencyError> {
+        {
+            let mut playlists = self.playlists.write().expect("Playlists lock poisoned");
+            playlists.retain(|saved| {
+                !(saved.guild_id == playlist.guild_id
+                    && saved.owner == playlist.owner
+                    && saved.is_named(&playlist.name))
+            });
+            playlists.push(playlist);
+        }
+        self.persist().await
+    }
+
+    /// Change a playlist of the owner, returns `None` if the owner has no such playlist
+    pub async fn update<T>(
+        &self,
+        guild_id: GuildId,
+        owner: UserId,
+        name: &str,
+        write: impl FnOnce(&mut SavedPlaylist) -> T,
+    ) -> Result<Option<T>, CadencyError> {
+        let result = {
+            let mut playlists = self.playlists.write().expect("Playlists lock poisoned");
+            playlists
+                .iter_mut()
+                .find(|playlist| {
+                    playlist.guild_id == guild_id
+                        && playlist.owner == owner
+                        && playlist.is_named(name)
+                })
+                .map(write)
+        };
+        if result.is_some() {
+            self.persist().await?;
+        }
+        Ok(result)
+    }
+
+    /// Delete a playlist of the owner and return it
+    pub async fn remove(
+        &self,
+        guild_id: GuildId,
+        owner: UserId,
+        name: &str,
+    ) -> Result<Option<SavedPlaylist>, CadencyError> {
+        let removed = {
+            let mut playlists = self.playlists.write().expect("Playlists lock poisoned");
+            playlists
+                .iter()
+                .position(|playlist| {
+                    playlist.guild_id == guild_id
+                        && playlist.owner == owner
+                        && playlist.is_named(name)
+                })
+                .map(|index| playlists.remove(index))
+        };
+        if removed.is_some() {
+            self.persist().await?;
+        }
+        Ok(removed)
+    }
+
+    /// Write the playlists to a temporary file first, so a crash can't leave a truncated file
+    async fn persist(&self) -> Result<(), CadencyError> {
+        if let Some(path) = &self.path {
+            let _file_lock = self.file_lock.lock().await;
+            let json = {
+                let playlists = self.playlists.read().expect("Playlists lock poisoned");
+                serde_json::to_string_pretty(&*playlists)?
+            };
+            let temp_path = path.with_extension("json.tmp");
+            tokio::fs::write(&temp_path, json).await?;
+            tokio::fs::rename(&temp_path, path).await?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn get_playlist_store(ctx: &Context) -> PlaylistStore {
+    let data = ctx.data.read().await;
+    data.get::<PlaylistStore>()
+        .expect("Expected PlaylistStore in TypeMap.")
+        .clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn playlist(name: &str, owner: u64, shared: bool) -> SavedPlaylist {
+        SavedPlaylist {
+            name: name.to_string(),
+            owner: UserId::new(owner),
+            guild_id: GuildId::new(1),
+            shared,
+            tracks: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_replace_playlist_with_same_name() {
+        let store = PlaylistStore::default();
+        store.save(playlist("Chill", 1, false)).await.unwrap();
+        store.save(playlist("chill", 1, true)).await.unwrap();
+        let playlists = store.list(GuildId::new(1), UserId::new(1));
+        assert_eq!(playlists.len(), 1);
+        assert!(playlists[0].shared);
+    }
+
+    #[tokio::test]
+    async fn should_only_show_shared_playlists_of_others() {
+        let store = PlaylistStore::default();
+        store.save(playlist("Private", 1, false)).await.unwrap();
+        store.save(playlist("Public", 1, true)).await.unwrap();
+        assert!(store
+            .get(GuildId::new(1), UserId::new(2), "private")
+            .is_none());
+        assert!(store
+            .get(GuildId::new(1), UserId::new(2), "public")
+            .is_some());
+        assert!(store
+            .get(GuildId::new(2), UserId::new(1), "public")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn should_write_all_concurrent_changes() {
+        let path = std::env::temp_dir().join(format!(
+            "cadency_playlists_test_{}.json",
+            std::process::id()
+        ));
+        let store = PlaylistStore::load(Some(path.clone())).unwrap();
+        let saves = (0..10).map(|index| {
+            let store = store.clone();
+            tokio::spawn(async move { store.save(playlist(&index.to_string(), 1, false)).await })
+        });
+        for save in saves.collect::<Vec<_>>() {
+            save.await.unwrap().unwrap();
+        }
+        let loaded = PlaylistStore::load(Some(path.clone())).unwrap();
+        assert_eq!(loaded.list(GuildId::new(1), UserId::new(1)).len(), 10);
+        assert!(!path.with_extension("json.tmp").exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_prefer_own_playlist() {
+        let store = PlaylistStore::default();
+        store.save(playlist("Mix", 1, true)).await.unwrap();
+        store.save(playlist("Mix", 2, false)).await.unwrap();
+        let found = store.get(GuildId::new(1), UserId::new(2), "mix").unwrap();
+        assert_eq!(found.owner, UserId::new(2));
+    }
+}
@@ -2,8 +2,8 @@ use crate::CadencyError;
 use derive_builder::Builder;
 use serenity::{
     builder::{
-        CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
-        EditInteractionResponse,
+        CreateActionRow, CreateAttachment, CreateEmbed, CreateInteractionResponse,
+        CreateInteractionResponseMessage, EditInteractionResponse,
     },
     model::prelude::CommandInteraction,
     prelude::Context,
@@ -23,6 +23,12 @@ pub struct Response {
     message: Option<String>,
     #[builder(default)]
     embeds: Vec<CreateEmbed>,
+    /// Files that are attached to the response
+    #[builder(default)]
+    attachments: Vec<CreateAttachment>,
+    /// Buttons and select menus, components of an edited response are replaced
+    #[builder(default)]
+    components: Vec<CreateActionRow>,
 }
 
 impl ResponseBuilder {
@@ -49,7 +55,9 @@ impl Response {
                         .add_embeds(self.embeds)
                 } else {
                     CreateInteractionResponseMessage::new().add_embeds(self.embeds)
-                };
+                }
+                .add_files(self.attachments)
+                .components(self.components);
                 command
                     .create_response(&ctx.http, CreateInteractionResponse::Message(response))
                     .await
@@ -71,7 +79,14 @@ impl Response {
                         .add_embeds(self.embeds)
                 } else {
                     EditInteractionResponse::new().add_embeds(self.embeds)
-                };
+                }
+                .components(self.components);
+                let edit_response = self
+                    .attachments
+                    .into_iter()
+                    .fold(edit_response, |edit_response, attachment| {
+                        edit_response.new_attachment(attachment)
+                    });
                 command
                     .edit_response(&ctx.http, edit_response)
                     .await
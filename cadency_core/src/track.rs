@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::{id::UserId, Timestamp};
+use songbird::input::AuxMetadata;
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// User data that is attached to every track in the queue
+#[derive(Debug)]
+pub struct TrackData {
+    pub metadata: AuxMetadata,
+    /// User that added the track to the queue
+    pub requester: UserId,
+    pub requested_at: Timestamp,
+    /// Url of the playlist the track was added from
+    pub playlist: Option<String>,
+    /// Users that voted to skip the track
+    pub skip_votes: Mutex<HashSet<UserId>>,
+    /// Set for radio streams that have no end
+    pub live: Option<LiveStream>,
+    /// The track was taken from the history again, so it isn't added to the history once more
+    pub replayed: AtomicBool,
+}
+
+impl TrackData {
+    pub fn new(metadata: AuxMetadata, requester: UserId, playlist: Option<String>) -> Self {
+        Self {
+            metadata,
+            requester,
+            requested_at: Timestamp::now(),
+            playlist,
+            skip_votes: Mutex::default(),
+            live: None,
+            replayed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.metadata.title.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.metadata.source_url.as_deref()
+    }
+
+    pub fn is_replayed(&self) -> bool {
+        self.replayed.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_replayed(&self) {
+        self.replayed.store(true, Ordering::SeqCst);
+    }
+
+    /// Register a skip vote of the user and return the amount of votes by the given listeners
+    pub fn vote_skip(&self, user_id: UserId, listeners: &[UserId]) -> usize {
+        let mut skip_votes = self.skip_votes.lock().expect("Skip votes lock poisoned");
+        skip_votes.insert(user_id);
+        // Votes of users that left the voice channel don't count anymore
+        listeners
+            .iter()
+            .filter(|listener| skip_votes.contains(listener))
+            .count()
+    }
+}
+
+/// State of a live stream like an internet radio
+#[derive(Debug, Default)]
+pub struct LiveStream {
+    /// Song that the radio station currently announces
+    title: Mutex<Option<String>>,
+    /// If the announced song is already being followed
+    watching: AtomicBool,
+}
+
+impl LiveStream {
+    pub fn title(&self) -> Option<String> {
+        self.title
+            .lock()
+            .expect("Stream title lock poisoned")
+            .clone()
+    }
+
+    pub fn set_title(&self, title: String) {
+        *self.title.lock().expect("Stream title lock poisoned") = Some(title);
+    }
+
+    /// Mark the stream as watched, returns false if it was already watched
+    pub fn start_watching(&self) -> bool {
+        !self.watching.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn stop_watching(&self) {
+        self.watching.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Description of a queued track that is enough to add it to the queue again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedTrack {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub requester: UserId,
+    pub playlist: Option<String>,
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    /// Title and duration were looked up by yt-dlp while the bot is running and can be trusted.
+    /// Tracks of files are looked up again, their duration could be made up.
+    #[serde(skip)]
+    pub verified: bool,
+}
+
+impl QueuedTrack {
+    /// The url of the track stops working after a while, so it can't be saved
+    pub fn is_expiring(&self) -> bool {
+        self.url
+            .as_deref()
+            .is_some_and(crate::utils::audio::is_expiring_url)
+    }
+
+    /// Payload for adding the track again and if the payload is an url
+    pub fn payload(&self) -> Option<(String, bool)> {
+        match (&self.url, &self.title) {
+            (Some(url), _) => Some((url.clone(), true)),
+            (None, Some(title)) => Some((title.clone(), false)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl From<&TrackData> for QueuedTrack {
+    fn from(track_data: &TrackData) -> Self {
+        Self {
+            title: track_data.title().map(str::to_owned),
+            url: track_data.url().map(str::to_owned),
+            requester: track_data.requester,
+            playlist: track_data.playlist.clone(),
+            duration: track_data.metadata.duration,
+            verified: true,
+        }
+    }
+}
+
+/// Amount of votes that are needed to skip a track
+pub fn required_skip_votes(listeners: usize, threshold: f32) -> usize {
+    ((listeners as f32 * threshold).ceil() as usize).max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_require_at_least_one_vote() {
+        assert_eq!(required_skip_votes(0, 0.5), 1);
+        assert_eq!(required_skip_votes(1, 0.1), 1);
+    }
+
+    #[test]
+    fn should_round_required_votes_up() {
+        assert_eq!(required_skip_votes(3, 0.5), 2);
+        assert_eq!(required_skip_votes(4, 0.5), 2);
+        assert_eq!(required_skip_votes(5, 1.0), 5);
+    }
+
+    #[test]
+    fn should_only_count_votes_of_listeners() {
+        let track_data = TrackData::new(AuxMetadata::default(), UserId::new(1), None);
+        let listeners = [UserId::new(2), UserId::new(3)];
+        assert_eq!(track_data.vote_skip(UserId::new(4), &listeners), 0);
+        assert_eq!(track_data.vote_skip(UserId::new(2), &listeners), 1);
+        assert_eq!(track_data.vote_skip(UserId::new(2), &listeners), 1);
+        assert_eq!(track_data.vote_skip(UserId::new(3), &listeners), 2);
+    }
+
+    #[test]
+    fn should_prefer_url_as_payload() {
+        let mut track = QueuedTrack {
+            title: Some("Title".to_string()),
+            url: Some("https://example.com".to_string()),
+            requester: UserId::new(1),
+            playlist: None,
+            duration: None,
+            verified: false,
+        };
+        assert_eq!(
+            track.payload(),
+            Some(("https://example.com".to_string(), true))
+        );
+        track.url = None;
+        assert_eq!(track.payload(), Some(("Title".to_string(), false)));
+        track.title = None;
+        assert_eq!(track.payload(), None);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::error::CadencyError;
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE},
+    Client,
+};
+use songbird::input::{codecs::get_probe, AudioStream, AuxMetadata, Compose, HttpRequest};
+use std::{path::Path, time::Duration};
+use symphonia::core::{
+    formats::{FormatOptions, FormatReader},
+    io::{MediaSource, MediaSourceStream},
+    meta::{Metadata, MetadataOptions, StandardTagKey, Tag},
+    probe::Hint,
+};
+
+/// How long to wait for the content type of an url
+const CONTENT_TYPE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Sites that are played with yt-dlp, their pages never serve audio directly
+const YTDLP_HOSTS: &[&str] = &[
+    "youtube.com",
+    "youtu.be",
+    "soundcloud.com",
+    "bandcamp.com",
+    "vimeo.com",
+    "twitch.tv",
+    "dailymotion.com",
+];
+/// Discord attachment links stop working after a while
+const EXPIRING_HOSTS: &[&str] = &["cdn.discordapp.com", "media.discordapp.net"];
+
+/// Audio that an url serves directly and that can be played without yt-dlp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectAudio {
+    /// Audio file that ends, its length may still be unknown
+    File,
+    /// Endless Icecast or Shoutcast stream
+    Radio { name: Option<String> },
+    /// HTTP live stream playlist
+    Hls,
+}
+
+impl DirectAudio {
+    /// Live streams have no duration and can't be seeked
+    pub fn is_live(&self) -> bool {
+        !matches!(self, Self::File)
+    }
+}
+
+fn mime_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Check if the content type describes an HTTP live stream playlist
+pub fn is_hls_content_type(content_type: &str) -> bool {
+    matches!(
+        mime_type(content_type).as_str(),
+        "application/vnd.apple.mpegurl"
+            | "application/x-mpegurl"
+            | "audio/mpegurl"
+            | "audio/x-mpegurl"
+    )
+}
+
+/// Check if the content type describes an audio file that can be played without yt-dlp
+pub fn is_audio_content_type(content_type: &str) -> bool {
+    let mime_type = mime_type(content_type);
+    (mime_type.starts_with("audio/") && !is_hls_content_type(&mime_type))
+        || mime_type == "application/ogg"
+}
+
+fn classify(url: &str, headers: &HeaderMap) -> Option<DirectAudio> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default();
+    let is_hls_path = reqwest::Url::parse(url).is_ok_and(|url| url.path().ends_with(".m3u8"));
+    if is_hls_content_type(content_type) || is_hls_path {
+        return Some(DirectAudio::Hls);
+    }
+    let is_audio = is_audio_content_type(content_type);
+    let is_icy = headers.keys().any(|key| key.as_str().starts_with("icy-"));
+    // Files can be sent without length as well, only the Icecast headers mark a stream
+    if is_icy {
+        let name = headers
+            .get("icy-name")
+            .and_then(|name| name.to_str().ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        return Some(DirectAudio::Radio { name });
+    }
+    is_audio.then_some(DirectAudio::File)
+}
+
+fn has_host(url: &str, hosts: &[&str]) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| {
+            hosts
+                .iter()
+                .any(|known| host == *known || host.ends_with(&format!(".{known}")))
+        })
+}
+
+/// Check if the url belongs to a site that is played with yt-dlp
+pub fn is_ytdlp_url(url: &str) -> bool {
+    has_host(url, YTDLP_HOSTS)
+}
+
+/// Check if the url stops working after a while, like the links of uploaded attachments.
+/// Those urls are not saved for later.
+pub fn is_expiring_url(url: &str) -> bool {
+    has_host(url, EXPIRING_HOSTS)
+}
+
+/// Ask the server of the url if it serves audio directly, sites of yt-dlp are not asked
+pub async fn detect_direct_audio(client: &Client, url: &str) -> Option<DirectAudio> {
+    if is_ytdlp_url(url) {
+        return None;
+    }
+    // Many stream servers don't answer HEAD requests, the body of the response is never read
+    let response = client
+        .get(url)
+        .header("Icy-MetaData", "1")
+        .timeout(CONTENT_TYPE_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    classify(url, response.headers())
+}
+
+/// Read the tags and the duration of an audio file by probing the beginning of the file
+pub async fn probe_metadata(client: Client, url: String) -> Result<AuxMetadata, CadencyError> {
+    let stream = HttpRequest::new(client, url.clone()).create_async().await?;
+    // Probing reads from the stream, which blocks the current thread
+    let metadata = tokio::task::spawn_blocking(move || read_metadata(stream))
+        .await
+        .map_err(|err| CadencyError::Runtime(format!("Failed to probe audio file: {err}")))?;
+    Ok(AuxMetadata {
+        title: metadata.title.or_else(|| file_name(&url)),
+        source_url: Some(url),
+        ..metadata
+    })
+}
+
+/// Metadata of a live stream, the station name is used as title
+pub fn live_metadata(url: String, name: Option<String>) -> AuxMetadata {
+    let title = name.or_else(|| {
+        reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned))
+    });
+    AuxMetadata {
+        title,
+        source_url: Some(url),
+        ..Default::default()
+    }
+}
+
+fn read_metadata(stream: AudioStream<Box<dyn MediaSource>>) -> AuxMetadata {
+    read_source_metadata(stream.input, stream.hint.unwrap_or_default())
+}
+
+/// Read the tags and the duration of a local audio file
+pub fn read_file_metadata(path: &Path) -> Result<AuxMetadata, CadencyError> {
+    let file = std::fs::File::open(path)?;
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    Ok(read_source_metadata(Box::new(file), hint))
+}
+
+fn read_source_metadata(source: Box<dyn MediaSource>, hint: Hint) -> AuxMetadata {
+    let media_stream = MediaSourceStream::new(source, Default::default());
+    let Ok(mut probed) = get_probe().format(
+        &hint,
+        media_stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) else {
+        return AuxMetadata::default();
+    };
+    let mut metadata = AuxMetadata::default();
+    // Tags can be stored in front of the container (e.g. ID3) or in the container itself
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(Metadata::current) {
+        apply_tags(&mut metadata, revision.tags());
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut metadata, revision.tags());
+    }
+    apply_track_info(&mut metadata, probed.format.as_ref());
+    metadata
+}
+
+fn apply_tags(metadata: &mut AuxMetadata, tags: &[Tag]) {
+    for tag in tags {
+        let value = Some(tag.value.to_string());
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => metadata.title = value,
+            Some(StandardTagKey::Artist) => metadata.artist = value,
+            Some(StandardTagKey::Album) => metadata.album = value,
+            Some(StandardTagKey::Date) => metadata.date = value,
+            _ => {}
+        }
+    }
+}
+
+fn apply_track_info(metadata: &mut AuxMetadata, format: &dyn FormatReader) {
+    let Some(track) = format.default_track() else {
+        return;
+    };
+    let params = &track.codec_params;
+    metadata.sample_rate = params.sample_rate;
+    metadata.channels = params.channels.map(|channels| channels.count() as u8);
+    if let (Some(time_base), Some(frames)) = (params.time_base, params.n_frames) {
+        let time = time_base.calc_time(frames);
+        metadata.duration =
+            Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac));
+    }
+}
+
+/// Name of the file in the url without extension, used when the file has no title tag
+fn file_name(url: &str) -> Option<String> {
+    let path = reqwest::Url::parse(url).ok()?.path().to_string();
+    let file_name = path.rsplit('/').next()?;
+    let file_name = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(name, _)| name);
+    Some(file_name.replace(['_', '-'], " ")).filter(|name| !name.trim().is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reqwest::header::CONTENT_LENGTH;
+
+    #[test]
+    fn should_detect_audio_content_types() {
+        assert!(is_audio_content_type("audio/mpeg"));
+        assert!(is_audio_content_type("Audio/OGG; charset=binary"));
+        assert!(is_audio_content_type("application/ogg"));
+        assert!(!is_audio_content_type("text/html; charset=utf-8"));
+        assert!(!is_audio_content_type("video/mp4"));
+        assert!(!is_audio_content_type("audio/x-mpegurl"));
+    }
+
+    #[test]
+    fn should_detect_live_streams() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "audio/mpeg".parse().unwrap());
+        headers.insert("icy-name", "Jazz Radio".parse().unwrap());
+        assert_eq!(
+            classify("https://radio.example.com/jazz", &headers),
+            Some(DirectAudio::Radio {
+                name: Some("Jazz Radio".to_string())
+            })
+        );
+        assert_eq!(
+            classify("https://radio.example.com/live.m3u8", &HeaderMap::new()),
+            Some(DirectAudio::Hls)
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "audio/mpeg".parse().unwrap());
+        headers.insert(CONTENT_LENGTH, "1024".parse().unwrap());
+        assert_eq!(
+            classify("https://example.com/song.mp3", &headers),
+            Some(DirectAudio::File)
+        );
+        headers.insert(CONTENT_TYPE, "text/html".parse().unwrap());
+        assert_eq!(classify("https://example.com/", &headers), None);
+    }
+
+    #[test]
+    fn should_not_treat_chunked_files_as_streams() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "audio/mpeg".parse().unwrap());
+        headers.insert("transfer-encoding", "chunked".parse().unwrap());
+        assert_eq!(
+            classify("https://example.com/song.mp3", &headers),
+            Some(DirectAudio::File)
+        );
+    }
+
+    #[test]
+    fn should_detect_hosts_by_domain() {
+        assert!(is_ytdlp_url("https://www.youtube.com/watch?v=A_MjCqQoLLA"));
+        assert!(is_ytdlp_url("https://artist.bandcamp.com/album/name"));
+        assert!(!is_ytdlp_url("https://notyoutube.com/song.mp3"));
+        assert!(is_expiring_url(
+            "https://cdn.discordapp.com/attachments/1/2/song.mp3?ex=1"
+        ));
+        assert!(!is_expiring_url("https://example.com/song.mp3"));
+    }
+
+    #[test]
+    fn should_use_file_name_as_title() {
+        assert_eq!(
+            file_name("https://cdn.example.com/attachments/1/my_song-final.mp3?ex=1"),
+            Some("my song final".to_string())
+        );
+        assert_eq!(file_name("https://example.com/"), None);
+    }
+}
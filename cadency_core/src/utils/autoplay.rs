@@ -0,0 +1,160 @@
+use crate::{
+    guild::get_guild_states,
+    track::{QueuedTrack, TrackData},
+    utils,
+};
+use cadency_yt_playlist::PlaylistItem;
+use serenity::{client::Context, model::id::GuildId};
+use std::{collections::HashSet, time::Duration};
+
+/// Amount of songs that are fetched from the YouTube Mix of the last track
+const MIX_SIZE: usize = 25;
+/// Amount of related songs that are tried when the first ones exceed the limits of the guild
+const CANDIDATES: usize = 5;
+
+/// Related songs that were not played recently and are not queued, in the order of the mix
+fn pick_candidates(items: Vec<PlaylistItem>, excluded: &HashSet<String>) -> Vec<PlaylistItem> {
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| !excluded.contains(&item.id) && seen.insert(item.id.clone()))
+        .take(CANDIDATES)
+        .collect()
+}
+
+/// Add a track that is related to the last track of the queue once it starts playing.
+/// Recently played tracks are not picked again.
+pub async fn queue_related_track(ctx: &Context, guild_id: GuildId) {
+    let guild_states = get_guild_states(ctx).await;
+    // Resuming the last track plays it again, only one lookup runs at a time
+    let should_run = guild_states.update(guild_id, |state| {
+        let should_run = state.settings.autoplay && !state.autoplay_in_flight;
+        state.autoplay_in_flight |= should_run;
+        should_run
+    });
+    if !should_run {
+        return;
+    }
+    add_related_track(ctx, guild_id).await;
+    guild_states.update(guild_id, |state| state.autoplay_in_flight = false);
+}
+
+async fn add_related_track(ctx: &Context, guild_id: GuildId) {
+    let guild_states = get_guild_states(ctx).await;
+    let (generation, history) = guild_states.get(guild_id, |state| {
+        (state.queue_generation, state.history.clone())
+    });
+    let Some(call) = utils::voice::get_songbird(ctx).await.get(guild_id) else {
+        return;
+    };
+    let (seed, mut excluded) = {
+        let handler = call.lock().await;
+        // Only the last track of the queue is followed by a related track
+        if handler.queue().len() != 1 {
+            return;
+        }
+        let queued_ids: HashSet<String> = handler
+            .queue()
+            .current_queue()
+            .iter()
+            .filter_map(|track| track.data::<TrackData>().url().map(str::to_owned))
+            .filter_map(|url| cadency_yt_playlist::video_id(&url))
+            .collect();
+        let seed = handler
+            .queue()
+            .current()
+            .and_then(|track| track.data::<TrackData>().url().map(str::to_owned))
+            .and_then(|url| cadency_yt_playlist::video_id(&url));
+        (seed, queued_ids)
+    };
+    // Tracks that are not on YouTube have no mix
+    let Some(seed) = seed else {
+        return;
+    };
+    excluded.extend(
+        history
+            .iter()
+            .filter_map(|track| track.url.as_deref())
+            .filter_map(cadency_yt_playlist::video_id),
+    );
+
+    debug!("Look up tracks related to '{seed}'");
+    let mix_seed = seed.clone();
+    let mix = tokio::task::spawn_blocking(move || {
+        cadency_yt_playlist::fetch_related_songs(&mix_seed, MIX_SIZE)
+    })
+    .await;
+    let items = match mix {
+        Ok(Ok(mix)) => mix.data,
+        Ok(Err(err)) => {
+            error!("❌ Failed to fetch tracks related to '{seed}': {err}");
+            return;
+        }
+        Err(err) => {
+            error!("❌ Related tracks task failed: {err}");
+            return;
+        }
+    };
+
+    // The queue may have been stopped or filled while the mix was fetched
+    let is_last_track = call.lock().await.queue().len() == 1;
+    if !is_last_track || guild_states.get(guild_id, |state| state.queue_generation) != generation {
+        return;
+    }
+    let requester = ctx.cache.current_user().id;
+    let tracks = pick_candidates(items, &excluded)
+        .into_iter()
+        .map(|item| QueuedTrack {
+            title: item.title,
+            url: Some(item.url),
+            requester,
+            playlist: None,
+            duration: item
+                .duration
+                .map(|duration| Duration::from_secs_f32(duration.max(0.0))),
+            verified: true,
+        })
+        .collect();
+    let summary = utils::voice::add_songs(ctx, call, guild_id, tracks, 1, None).await;
+    if summary.added == 0 {
+        debug!("No related track of '{seed}' could be added");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(id: &str) -> PlaylistItem {
+        PlaylistItem {
+            id: id.to_string(),
+            playlist_index: 0,
+            duration: Some(200.0),
+            title: Some(id.to_string()),
+            url: format!("https://www.youtube.com/watch?v={id}"),
+        }
+    }
+
+    #[test]
+    fn should_skip_recent_and_queued_tracks() {
+        let excluded = HashSet::from(["seed".to_string(), "played".to_string()]);
+        let candidates = pick_candidates(
+            vec![
+                item("seed"),
+                item("a"),
+                item("played"),
+                item("a"),
+                item("b"),
+            ],
+            &excluded,
+        );
+        let ids: Vec<&str> = candidates.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn should_limit_candidates() {
+        let items = (0..10).map(|index| item(&index.to_string())).collect();
+        assert_eq!(pick_candidates(items, &HashSet::new()).len(), CANDIDATES);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    error::CadencyError,
+    guild::get_guild_states,
+    track::{required_skip_votes, TrackData},
+    utils::{
+        self,
+        policy::{Controller, MusicAction},
+    },
+};
+use rand::seq::SliceRandom;
+use serenity::{builder::CreateEmbed, client::Context, model::colour::Colour, prelude::Mutex};
+use songbird::Call;
+use std::sync::Arc;
+
+/// How often the current track is repeated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    Infinite,
+    Times(usize),
+    Off,
+}
+
+async fn get_call(
+    ctx: &Context,
+    controller: &Controller<'_>,
+) -> Result<Arc<Mutex<Call>>, CadencyError> {
+    utils::voice::get_songbird(ctx)
+        .await
+        .get(controller.guild_id)
+        .ok_or(CadencyError::Command {
+            message: "❌ **No active voice session on the server**".to_string(),
+        })
+}
+
+pub async fn pause(
+    ctx: &Context,
+    controller: &Controller<'_>,
+) -> Result<CreateEmbed, CadencyError> {
+    let call = get_call(ctx, controller).await?;
+    utils::policy::check_controller_policy(ctx, controller, MusicAction::Destructive).await?;
+    let handler = call.lock().await;
+    let embed = CreateEmbed::default()
+        .title("⏸️ Pause")
+        .color(Colour::from_rgb(255, 165, 0)); // Orange
+    if handler.queue().is_empty() {
+        return Ok(
+            embed.description("❌ **Nothing to pause**\n\nThere are no tracks currently playing.")
+        );
+    }
+    handler.queue().pause().map_err(|err| {
+        error!("Failed to pause: {err:?}");
+        CadencyError::Command {
+            message: "❌ **Could not pause the track**".to_string(),
+        }
+    })?;
+    Ok(embed.description("✅ **Paused**\n\nPlayback has been paused. Use `/resume` to continue."))
+}
+
+pub async fn resume(
+    ctx: &Context,
+    controller: &Controller<'_>,
+) -> Result<CreateEmbed, CadencyError> {
+    let call = get_call(ctx, controller).await?;
+    utils::policy::check_controller_policy(ctx, controller, MusicAction::Control).await?;
+    let handler = call.lock().await;
+    let embed = CreateEmbed::default()
+        .title("▶️ Resume")
+        .color(Colour::from_rgb(0, 255, 0)); // Lime
+    if handler.queue().is_empty() {
+        return Ok(
+            embed.description("❌ **Nothing to resume**\n\nThere are no tracks in the queue.")
+        );
+    }
+    handler.queue().resume().map_err(|err| {
+        error!("Failed to resume: {err:?}");
+        CadencyError::Command {
+            message: "❌ **Could not resume**".to_string(),
+        }
+    })?;
+    Ok(embed.description("✅ **Resumed**\n\nPlayback has been resumed!"))
+}
+
+/// Skip the current track, or register a vote to skip it if the guild uses vote skipping
+pub async fn skip(ctx: &Context, controller: &Controller<'_>) -> Result<CreateEmbed, CadencyError> {
+    let guild_id = controller.guild_id;
+    let call = get_call(ctx, controller).await?;
+    let handler = call.lock().await;
+    let embed = CreateEmbed::default()
+        .title("⏭️ Skip")
+        .color(Colour::from_rgb(255, 215, 0)); // Gold
+    let Some(current_track) = handler.queue().current() else {
+        return Ok(
+            embed.description("❌ **Nothing to skip**\n\nThere are no tracks currently playing.")
+        );
+    };
+    let track_data = current_track.data::<TrackData>();
+    let (vote_skip_threshold, is_dj) = get_guild_states(ctx).await.get(guild_id, |state| {
+        (
+            state.settings.vote_skip_threshold,
+            state.settings.is_dj(controller.member),
+        )
+    });
+
+    // Without vote skipping a skip is a destructive action, otherwise everyone can vote
+    let action = if vote_skip_threshold.is_some() {
+        MusicAction::Control
+    } else {
+        MusicAction::Destructive
+    };
+    utils::policy::check_controller_policy(ctx, controller, action).await?;
+
+    // The requester of the track and DJs can skip without a vote
+    let description = match vote_skip_threshold {
+        Some(threshold) if !is_dj && track_data.requester != controller.user_id => {
+            let listeners = utils::voice::get_bot_listeners(ctx, guild_id);
+            if !listeners.contains(&controller.user_id) {
+                return Err(CadencyError::Command {
+                    message: "❌ **You have to be in the voice channel of the bot to vote**"
+                        .to_string(),
+                });
+            }
+            let votes = track_data.vote_skip(controller.user_id, &listeners);
+            let required_votes = required_skip_votes(listeners.len(), threshold);
+            if votes < required_votes {
+                return Ok(embed.description(format!(
+                    "🗳️ **Vote registered**\n\n**{votes}/{required_votes}** votes are needed to skip the track."
+                )));
+            }
+            format!("✅ **Skipped**\n\nThe vote passed with **{votes}/{required_votes}** votes.")
+        }
+        _ => "✅ **Skipped**\n\nMoving to the next track in queue.".to_string(),
+    };
+    handler.queue().skip().map_err(|err| {
+        error!("Failed to skip: {err:?}");
+        CadencyError::Command {
+            message: "❌ **Could not skip the track**".to_string(),
+        }
+    })?;
+    Ok(embed.description(description))
+}
+
+/// Change how often the current track is repeated
+pub async fn set_loop(
+    ctx: &Context,
+    controller: &Controller<'_>,
+    mode: LoopMode,
+) -> Result<CreateEmbed, CadencyError> {
+    let call = get_call(ctx, controller).await?;
+    utils::policy::check_controller_policy(ctx, controller, MusicAction::Destructive).await?;
+    let handler = call.lock().await;
+    let track = handler.queue().current().ok_or(CadencyError::Command {
+        message: "❌ **No song is playing**".to_string(),
+    })?;
+    let embed = CreateEmbed::default()
+        .title("🔁 Track Loop")
+        .color(Colour::from_rgb(255, 140, 0)); // Dark orange
+    match mode {
+        LoopMode::Off => {
+            track.disable_loop().map_err(|err| {
+                error!("Could not disable loop: {}", err);
+                CadencyError::Command {
+                    message: "❌ **Could not disable loop**".to_string(),
+                }
+            })?;
+            Ok(embed.description("✅ **Loop Disabled**\n\nThe track will play only once."))
+        }
+        LoopMode::Times(amount) => {
+            track.loop_for(amount).map_err(|err| {
+                error!("Could not loop track '{amount}' times: {}", err);
+                CadencyError::Command {
+                    message: "❌ **Could not loop track**".to_string(),
+                }
+            })?;
+            Ok(embed.description(format!(
+                "✅ **Loop Enabled**\n\n🔁 The current track will loop **{}** times.",
+                amount
+            )))
+        }
+        LoopMode::Infinite => {
+            track.enable_loop().map_err(|err| {
+                error!("Could not loop track infinite: {}", err);
+                CadencyError::Command {
+                    message: "❌ **Could not loop track**".to_string(),
+                }
+            })?;
+            Ok(embed.description(
+                "✅ **Loop Enabled**\n\n🔁 The current track will loop **infinitely**.",
+            ))
+        }
+    }
+}
+
+/// Shuffle the upcoming tracks, the current track keeps playing
+pub async fn shuffle(
+    ctx: &Context,
+    controller: &Controller<'_>,
+) -> Result<CreateEmbed, CadencyError> {
+    let call = get_call(ctx, controller).await?;
+    utils::policy::check_controller_policy(ctx, controller, MusicAction::Destructive).await?;
+    let handler = call.lock().await;
+    let embed = CreateEmbed::default()
+        .title("🔀 Shuffle")
+        .color(Colour::from_rgb(0, 206, 209)); // Dark turquoise
+    let upcoming = handler.queue().len().saturating_sub(1);
+    if upcoming < 2 {
+        return Ok(embed.description(
+            "❌ **Nothing to shuffle**\n\nThere are less than two upcoming tracks in the queue.",
+        ));
+    }
+    handler.queue().modify_queue(|queue| {
+        queue.make_contiguous()[1..].shuffle(&mut rand::rng());
+    });
+    Ok(embed.description(format!(
+        "✅ **Shuffled**\n\nThe order of **{upcoming}** upcoming tracks was shuffled."
+    )))
+}
+
+/// Stop the playback, clear the queue and stop adding tracks that are still loading
+pub async fn stop(ctx: &Context, controller: &Controller<'_>) -> Result<CreateEmbed, CadencyError> {
+    let call = get_call(ctx, controller).await?;
+    utils::policy::check_controller_policy(ctx, controller, MusicAction::Destructive).await?;
+    get_guild_states(ctx)
+        .await
+        .update(controller.guild_id, |state| state.queue_generation += 1);
+
+    let handler = call.lock().await;
+    let embed = CreateEmbed::default()
+        .title("🛑 Stop & Clear")
+        .color(Colour::from_rgb(255, 69, 0)); // Orange red
+    if handler.queue().is_empty() {
+        return Ok(embed.description("❌ **Nothing to stop**\n\nThere are no tracks in the queue."));
+    }
+    handler.queue().stop();
+    Ok(embed.description("✅ **Stopped**\n\nCleared the queue and stopped playback."))
+}
@@ -5,6 +5,13 @@ use crate::{
 use serenity::client::Context;
 use std::sync::Arc;
 
+pub mod audio;
+pub mod autoplay;
+pub mod controls;
+pub mod pages;
+pub mod policy;
+pub mod queue;
+pub mod radio;
 pub mod voice;
 
 pub(crate) async fn get_commands(ctx: &Context) -> Vec<Arc<dyn CadencyCommand>> {
@@ -0,0 +1,87 @@
+use crate::error::CadencyError;
+use serenity::{
+    builder::{
+        CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse,
+        CreateInteractionResponseMessage, EditInteractionResponse,
+    },
+    client::Context,
+    model::application::CommandInteraction,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long the pages can be turned after the last click
+const PAGE_TIMEOUT: Duration = Duration::from_secs(120);
+/// Responses to interactions can only be edited for 15 minutes
+const PAGES_DEADLINE: Duration = Duration::from_secs(14 * 60);
+
+/// Buttons to turn to the previous and the next page, their ids start with the prefix
+pub fn page_buttons(prefix: &str, page: usize, page_count: usize) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{prefix}_previous"))
+            .emoji('◀')
+            .label("Previous")
+            .disabled(page == 0),
+        CreateButton::new(format!("{prefix}_next"))
+            .emoji('▶')
+            .label("Next")
+            .disabled(page + 1 >= page_count),
+    ])]
+}
+
+/// Show the first page with buttons and turn the pages on every click.
+/// Clicks renew the timeout, but the pages can't be turned after the deadline.
+/// Returns the page that was shown last, the response should end with it.
+pub async fn turn_pages(
+    ctx: &Context,
+    command: &CommandInteraction,
+    prefix: &str,
+    page_count: usize,
+    embed: impl Fn(usize) -> CreateEmbed,
+) -> Result<usize, CadencyError> {
+    let message = command
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new()
+                .embed(embed(0))
+                .components(page_buttons(prefix, 0, page_count)),
+        )
+        .await
+        .map_err(|err| {
+            error!("Failed to show the pages: {err}");
+            CadencyError::Response
+        })?;
+    let previous_id = format!("{prefix}_previous");
+    let next_id = format!("{prefix}_next");
+    let mut page: usize = 0;
+    let deadline = Instant::now() + PAGES_DEADLINE;
+    loop {
+        let timeout = PAGE_TIMEOUT.min(deadline.saturating_duration_since(Instant::now()));
+        if timeout.is_zero() {
+            break;
+        }
+        let Some(press) = message
+            .await_component_interaction(&ctx.shard)
+            .timeout(timeout)
+            .await
+        else {
+            break;
+        };
+        let custom_id = press.data.custom_id.as_str();
+        if custom_id == previous_id {
+            page = page.saturating_sub(1);
+        } else if custom_id == next_id {
+            page = (page + 1).min(page_count - 1);
+        }
+        let update = CreateInteractionResponseMessage::new()
+            .embed(embed(page))
+            .components(page_buttons(prefix, page, page_count));
+        if let Err(err) = press
+            .create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(update))
+            .await
+        {
+            error!("Failed to turn the page: {err}");
+        }
+    }
+    Ok(page)
+}
@@ -0,0 +1,185 @@
+use crate::{error::CadencyError, guild::get_guild_states, utils};
+use serenity::{
+    client::Context,
+    model::{
+        application::{CommandInteraction, ComponentInteraction},
+        guild::Member,
+        id::{GuildId, UserId},
+    },
+};
+
+/// User that controls the playback of a guild, either by a command or by a button
+#[derive(Debug, Clone, Copy)]
+pub struct Controller<'a> {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub member: Option<&'a Member>,
+}
+
+impl<'a> Controller<'a> {
+    fn new(
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+        member: Option<&'a Member>,
+    ) -> Result<Self, CadencyError> {
+        let guild_id = guild_id.ok_or(CadencyError::Command {
+            message: "❌ **This command can only be executed on a server**".to_string(),
+        })?;
+        Ok(Self {
+            guild_id,
+            user_id,
+            member,
+        })
+    }
+
+    pub fn from_command(command: &'a CommandInteraction) -> Result<Self, CadencyError> {
+        Self::new(command.guild_id, command.user.id, command.member.as_deref())
+    }
+
+    pub fn from_component(interaction: &'a ComponentInteraction) -> Result<Self, CadencyError> {
+        Self::new(
+            interaction.guild_id,
+            interaction.user.id,
+            interaction.member.as_ref(),
+        )
+    }
+}
+
+/// Kind of playback control a command performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicAction {
+    /// Controls the playback without removing anything, e.g. resume
+    Control,
+    /// Interrupts or changes the playback for everyone, e.g. stop or skip
+    Destructive,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Decision {
+    Allow,
+    NotInChannel,
+    NotDj,
+}
+
+fn decide(
+    action: MusicAction,
+    user_id: UserId,
+    listeners: &[UserId],
+    is_dj: bool,
+    has_dj_role: bool,
+    same_channel_only: bool,
+) -> Decision {
+    let in_channel = listeners.contains(&user_id);
+    // A user that listens alone can't disturb anyone
+    if is_dj || (in_channel && listeners.len() == 1) {
+        Decision::Allow
+    } else if same_channel_only && !in_channel {
+        Decision::NotInChannel
+    } else if action == MusicAction::Destructive && has_dj_role {
+        Decision::NotDj
+    } else {
+        Decision::Allow
+    }
+}
+
+/// Check the music policy of the guild before a user controls the playback
+pub async fn check_music_policy(
+    ctx: &Context,
+    command: &CommandInteraction,
+    action: MusicAction,
+) -> Result<(), CadencyError> {
+    match Controller::from_command(command) {
+        Ok(controller) => check_controller_policy(ctx, &controller, action).await,
+        Err(_) => Ok(()),
+    }
+}
+
+/// Check the music policy of the guild before the controller changes the playback
+pub async fn check_controller_policy(
+    ctx: &Context,
+    controller: &Controller<'_>,
+    action: MusicAction,
+) -> Result<(), CadencyError> {
+    let guild_id = controller.guild_id;
+    let (is_dj, has_dj_role, same_channel_only) =
+        get_guild_states(ctx).await.get(guild_id, |state| {
+            (
+                state.settings.is_dj(controller.member),
+                state.settings.dj_role.is_some(),
+                state.settings.same_channel_only,
+            )
+        });
+    let listeners = utils::voice::get_bot_listeners(ctx, guild_id);
+    match decide(
+        action,
+        controller.user_id,
+        &listeners,
+        is_dj,
+        has_dj_role,
+        same_channel_only,
+    ) {
+        Decision::Allow => Ok(()),
+        Decision::NotInChannel => Err(CadencyError::Command {
+            message: "❌ **You have to be in the same voice channel as the bot**".to_string(),
+        }),
+        Decision::NotDj => Err(CadencyError::Command {
+            message: "❌ **Only DJs can use this command**".to_string(),
+        }),
+    }
+}
+
+/// Check if a user is a DJ by the cached member of the guild.
+/// Used where only the user is known, e.g. when tracks are added to the queue.
+pub async fn is_dj_user(ctx: &Context, guild_id: GuildId, user_id: UserId) -> bool {
+    let member = ctx.cache.guild(guild_id).and_then(|guild| {
+        guild.members.get(&user_id).map(|member| {
+            let mut member = member.clone();
+            // Cached members don't know their permissions
+            member.permissions = Some(guild.member_permissions(&member));
+            member
+        })
+    });
+    get_guild_states(ctx)
+        .await
+        .get(guild_id, |state| state.settings.is_dj(member.as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const USER: UserId = UserId::new(1);
+    const OTHER: UserId = UserId::new(2);
+
+    #[test]
+    fn should_require_same_channel() {
+        let decision = decide(MusicAction::Control, USER, &[OTHER], false, false, true);
+        assert_eq!(decision, Decision::NotInChannel);
+        let decision = decide(MusicAction::Control, USER, &[OTHER], false, false, false);
+        assert_eq!(decision, Decision::Allow);
+    }
+
+    #[test]
+    fn should_require_dj_for_destructive_actions() {
+        let listeners = [USER, OTHER];
+        let decision = decide(
+            MusicAction::Destructive,
+            USER,
+            &listeners,
+            false,
+            true,
+            true,
+        );
+        assert_eq!(decision, Decision::NotDj);
+        let decision = decide(MusicAction::Control, USER, &listeners, false, true, true);
+        assert_eq!(decision, Decision::Allow);
+        let decision = decide(MusicAction::Destructive, USER, &listeners, true, true, true);
+        assert_eq!(decision, Decision::Allow);
+    }
+
+    #[test]
+    fn should_allow_lonely_listener() {
+        let decision = decide(MusicAction::Destructive, USER, &[USER], false, true, true);
+        assert_eq!(decision, Decision::Allow);
+    }
+}
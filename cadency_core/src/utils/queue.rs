@@ -0,0 +1,264 @@
+use crate::{
+    track::{QueuedTrack, TrackData},
+    utils,
+};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::Context,
+    model::id::{GuildId, UserId},
+    prelude::Mutex,
+};
+use songbird::{tracks::TrackQueue, Call};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    sync::Arc,
+    time::Duration,
+};
+
+/// Queued tracks and the playback position of the current track
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub tracks: Vec<QueuedTrack>,
+    pub position: Duration,
+}
+
+/// Reorder the items in rounds, so that every key gets one item per round.
+/// Keys are served in the order of their first appearance and the items of a key keep their order.
+pub(crate) fn round_robin<T, K: Eq + Hash>(items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<T> {
+    let amount = items.len();
+    let mut groups: Vec<VecDeque<T>> = Vec::new();
+    let mut group_indices: HashMap<K, usize> = HashMap::new();
+    for item in items {
+        let group_index = *group_indices.entry(key(&item)).or_insert_with(|| {
+            groups.push(VecDeque::new());
+            groups.len() - 1
+        });
+        groups[group_index].push_back(item);
+    }
+
+    let mut ordered = Vec::with_capacity(amount);
+    while ordered.len() < amount {
+        ordered.extend(groups.iter_mut().filter_map(VecDeque::pop_front));
+    }
+    ordered
+}
+
+/// Interleave the queued tracks by their requesters.
+/// The current track stays in place as it is always the first track of the first requester.
+pub fn apply_fair_order(queue: &TrackQueue) {
+    queue.modify_queue(|tracks| {
+        let ordered = round_robin(tracks.drain(..).collect(), |track| {
+            track.data::<TrackData>().requester
+        });
+        tracks.extend(ordered);
+    });
+}
+
+/// Amount of tracks in the queue that have been requested by the user
+pub fn count_user_tracks(queue: &TrackQueue, user_id: UserId) -> usize {
+    queue.modify_queue(|tracks| {
+        tracks
+            .iter()
+            .filter(|track| track.data::<TrackData>().requester == user_id)
+            .count()
+    })
+}
+
+/// Playing time that is left in the queue, tracks without duration are not counted
+pub async fn remaining_duration(queue: &TrackQueue) -> Duration {
+    let tracks = queue.current_queue();
+    let total: Duration = tracks
+        .iter()
+        .filter_map(|track| track.data::<TrackData>().metadata.duration)
+        .sum();
+    let played = match tracks.first() {
+        Some(current) => current
+            .get_info()
+            .await
+            .map(|info| info.position)
+            .unwrap_or_default(),
+        None => Duration::ZERO,
+    };
+    total.saturating_sub(played)
+}
+
+/// Estimated time until each track of the queue starts playing, the first track plays already.
+/// The estimate is unknown after the current track if its remaining time is unknown,
+/// and after any track without duration.
+pub fn estimated_start_times(
+    current_remaining: Option<Duration>,
+    durations: &[Option<Duration>],
+) -> Vec<Option<Duration>> {
+    let mut start = current_remaining;
+    durations
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| {
+            if index == 0 {
+                return Some(Duration::ZERO);
+            }
+            let track_start = start;
+            start = start
+                .zip(*duration)
+                .map(|(start, duration)| start + duration);
+            track_start
+        })
+        .collect()
+}
+
+/// Remember the queued tracks to be able to add them again later
+pub async fn snapshot_queue(queue: &TrackQueue) -> QueueSnapshot {
+    let tracks = queue.current_queue();
+    let position = match tracks.first() {
+        Some(track) => track
+            .get_info()
+            .await
+            .map(|track_state| track_state.position)
+            .unwrap_or_default(),
+        None => Duration::ZERO,
+    };
+    QueueSnapshot {
+        tracks: tracks
+            .iter()
+            .map(|track| QueuedTrack::from(track.data::<TrackData>().as_ref()))
+            .collect(),
+        position,
+    }
+}
+
+impl QueueSnapshot {
+    /// Remove the tracks whose urls expire, the position is kept only if the current track stays
+    pub fn without_expiring(mut self) -> Self {
+        if self.tracks.first().is_some_and(QueuedTrack::is_expiring) {
+            self.position = Duration::ZERO;
+        }
+        self.tracks.retain(|track| !track.is_expiring());
+        self
+    }
+}
+
+/// Add the tracks of the snapshot again if the queue of the call got lost.
+/// Returns the amount of restored tracks.
+pub async fn restore_queue(
+    ctx: &Context,
+    call: Arc<Mutex<Call>>,
+    guild_id: GuildId,
+    snapshot: QueueSnapshot,
+) -> usize {
+    {
+        let handler = call.lock().await;
+        // The queue survived if the current track can still be controlled
+        if let Some(track) = handler.queue().current() {
+            if track.get_info().await.is_ok() {
+                return 0;
+            }
+        }
+        handler.queue().stop();
+    }
+    let mut restored = 0;
+    for (index, track) in snapshot.tracks.into_iter().enumerate() {
+        let Some((payload, is_url)) = track.payload() else {
+            continue;
+        };
+        match utils::voice::add_song(
+            ctx,
+            call.clone(),
+            guild_id,
+            payload,
+            is_url,
+            track.requester,
+            track.playlist,
+        )
+        .await
+        {
+            Ok((_, track_handle)) => {
+                // Continue the first track where it stopped
+                if index == 0 && !snapshot.position.is_zero() {
+                    drop(track_handle.seek(snapshot.position));
+                }
+                restored += 1;
+            }
+            Err(err) => warn!("Failed to restore track {:?}: {err}", track.title),
+        }
+    }
+    restored
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use songbird::tracks::Track;
+
+    #[test]
+    fn should_estimate_start_times() {
+        let minutes = |minutes: u64| Some(Duration::from_secs(minutes * 60));
+        assert_eq!(
+            estimated_start_times(minutes(2), &[minutes(5), minutes(3), None, minutes(4)]),
+            vec![Some(Duration::ZERO), minutes(2), minutes(5), None]
+        );
+        assert_eq!(
+            estimated_start_times(None, &[None, minutes(3)]),
+            vec![Some(Duration::ZERO), None]
+        );
+    }
+
+    #[test]
+    fn should_interleave_items_by_key() {
+        let items = vec![("a", 1), ("a", 2), ("a", 3), ("b", 1), ("c", 1), ("b", 2)];
+        let ordered = round_robin(items, |(key, _)| *key);
+        assert_eq!(
+            ordered,
+            vec![("a", 1), ("b", 1), ("c", 1), ("a", 2), ("b", 2), ("a", 3)]
+        );
+    }
+
+    #[test]
+    fn should_keep_order_of_single_key() {
+        let items = vec![1, 2, 3, 4];
+        assert_eq!(round_robin(items, |_| "a"), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn should_keep_first_item_in_place() {
+        let items = vec![("b", 0), ("a", 1), ("a", 2), ("b", 1)];
+        let ordered = round_robin(items, |(key, _)| *key);
+        assert_eq!(ordered.first(), Some(&("b", 0)));
+    }
+
+    #[tokio::test]
+    async fn should_count_live_tracks_of_user() {
+        let mut driver = songbird::Driver::default();
+        let user_id = UserId::new(1);
+        let live_track = TrackData {
+            live: Some(crate::track::LiveStream::default()),
+            ..TrackData::new(Default::default(), user_id, None)
+        };
+        let input: songbird::input::Input = vec![0_u8; 16].into();
+        driver.enqueue_with_preload(Track::new_with_data(input, Arc::new(live_track)), None);
+        assert_eq!(count_user_tracks(driver.queue(), user_id), 1);
+        assert_eq!(count_user_tracks(driver.queue(), UserId::new(2)), 0);
+    }
+
+    #[test]
+    fn should_leave_out_expiring_tracks() {
+        let track = |url: &str| QueuedTrack {
+            title: None,
+            url: Some(url.to_string()),
+            requester: UserId::new(1),
+            playlist: None,
+            duration: None,
+            verified: false,
+        };
+        let snapshot = QueueSnapshot {
+            tracks: vec![
+                track("https://cdn.discordapp.com/attachments/1/2/song.mp3"),
+                track("https://example.com/song.mp3"),
+            ],
+            position: Duration::from_secs(42),
+        }
+        .without_expiring();
+        assert_eq!(snapshot.tracks.len(), 1);
+        assert_eq!(snapshot.position, Duration::ZERO);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::track::TrackData;
+use reqwest::Client;
+use songbird::tracks::TrackHandle;
+use std::time::{Duration, Instant};
+
+/// How often the stream title watcher checks if the track is still playing
+const TRACK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+enum IcyState {
+    /// Audio bytes until the next metadata block
+    Audio(usize),
+    /// The next byte is the length of the metadata block in 16 byte steps
+    Length,
+    Metadata {
+        length: usize,
+        data: Vec<u8>,
+    },
+}
+
+/// Reads the stream titles of an Icecast or Shoutcast stream with interleaved metadata
+#[derive(Debug)]
+pub struct IcyMetadataReader {
+    /// Amount of audio bytes between two metadata blocks
+    metaint: usize,
+    state: IcyState,
+}
+
+impl IcyMetadataReader {
+    pub fn new(metaint: usize) -> Self {
+        Self {
+            metaint,
+            state: IcyState::Audio(metaint),
+        }
+    }
+
+    /// Read the next bytes of the stream and return the announced stream titles
+    pub fn feed(&mut self, mut bytes: &[u8]) -> Vec<String> {
+        let mut titles = Vec::new();
+        while !bytes.is_empty() {
+            let next_state = match &mut self.state {
+                IcyState::Audio(remaining) => {
+                    let read = (*remaining).min(bytes.len());
+                    bytes = &bytes[read..];
+                    *remaining -= read;
+                    (*remaining == 0).then_some(IcyState::Length)
+                }
+                IcyState::Length => {
+                    let length = usize::from(bytes[0]) * 16;
+                    bytes = &bytes[1..];
+                    Some(if length == 0 {
+                        IcyState::Audio(self.metaint)
+                    } else {
+                        IcyState::Metadata {
+                            length,
+                            data: Vec::with_capacity(length),
+                        }
+                    })
+                }
+                IcyState::Metadata { length, data } => {
+                    let read = (*length - data.len()).min(bytes.len());
+                    data.extend_from_slice(&bytes[..read]);
+                    bytes = &bytes[read..];
+                    if data.len() == *length {
+                        titles.extend(parse_stream_title(&String::from_utf8_lossy(data)));
+                        Some(IcyState::Audio(self.metaint))
+                    } else {
+                        None
+                    }
+                }
+            };
+            if let Some(next_state) = next_state {
+                self.state = next_state;
+            }
+        }
+        titles
+    }
+}
+
+/// Read the title out of a metadata block like `StreamTitle='Artist - Song';StreamUrl='';`
+fn parse_stream_title(metadata: &str) -> Option<String> {
+    let start = metadata.find("StreamTitle='")? + "StreamTitle='".len();
+    let title = &metadata[start..];
+    // Titles may contain quotes, only a quote followed by a semicolon ends the title
+    let title = title
+        .find("';")
+        .map_or(title.trim_end_matches(['\0', '\'']), |end| &title[..end]);
+    Some(title.trim().to_string()).filter(|title| !title.is_empty())
+}
+
+/// Follow the titles that a radio stream announces and store them in the track data
+/// until the track ends. This opens a second connection to the stream, because the
+/// audio source doesn't request the metadata.
+pub async fn watch_stream_title(client: Client, track: TrackHandle) {
+    let track_data = track.data::<TrackData>();
+    let (Some(live), Some(url)) = (&track_data.live, track_data.url()) else {
+        return;
+    };
+    let metaint = match client.get(url).header("Icy-MetaData", "1").send().await {
+        Ok(response) => response
+            .headers()
+            .get("icy-metaint")
+            .and_then(|metaint| metaint.to_str().ok())
+            .and_then(|metaint| metaint.parse::<usize>().ok())
+            .filter(|metaint| *metaint > 0)
+            .map(|metaint| (response, metaint)),
+        Err(err) => {
+            warn!("Failed to connect to radio stream: {err}");
+            None
+        }
+    };
+    let Some((mut response, metaint)) = metaint else {
+        debug!("Radio stream '{url}' doesn't announce titles");
+        live.stop_watching();
+        return;
+    };
+    let mut reader = IcyMetadataReader::new(metaint);
+    let mut last_check = Instant::now();
+    while let Ok(Some(chunk)) = response.chunk().await {
+        if let Some(title) = reader.feed(&chunk).pop() {
+            debug!("Radio stream title changed to '{title}'");
+            live.set_title(title);
+        }
+        if last_check.elapsed() >= TRACK_CHECK_INTERVAL {
+            last_check = Instant::now();
+            let is_done = track
+                .get_info()
+                .await
+                .map_or(true, |info| info.playing.is_done());
+            if is_done {
+                break;
+            }
+        }
+    }
+    live.stop_watching();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_parse_stream_title() {
+        assert_eq!(
+            parse_stream_title("StreamTitle='Miles Davis - So What';StreamUrl='';\0\0"),
+            Some("Miles Davis - So What".to_string())
+        );
+        assert_eq!(
+            parse_stream_title("StreamTitle='Rock 'n' Roll';"),
+            Some("Rock 'n' Roll".to_string())
+        );
+        assert_eq!(parse_stream_title("StreamTitle='';"), None);
+    }
+
+    #[test]
+    fn should_read_titles_between_audio() {
+        let metadata = b"StreamTitle='Song';";
+        let mut block = metadata.to_vec();
+        block.resize(32, 0);
+        let mut stream = vec![1_u8; 4];
+        stream.push(2);
+        stream.extend_from_slice(&block);
+        stream.extend_from_slice(&[1, 1, 1, 1, 0, 1, 1]);
+
+        let mut reader = IcyMetadataReader::new(4);
+        // Feed the stream in small chunks to split the metadata block
+        let titles: Vec<String> = stream
+            .chunks(3)
+            .flat_map(|chunk| reader.feed(chunk))
+            .collect();
+        assert_eq!(titles, vec!["Song".to_string()]);
+    }
+}
@@ -1,18 +1,33 @@
-use std::convert::Infallible;
+use std::{collections::VecDeque, convert::Infallible, time::Duration};
 
-use crate::{error::CadencyError, http::get_http_client, utils};
+use crate::{
+    error::CadencyError,
+    filter::FilteredInput,
+    guild::get_guild_states,
+    handler::voice::{
+        register_voice_handlers, AutoplayHandler, HistoryHandler, StreamTitleHandler,
+        UnavailableTrackHandler,
+    },
+    http::get_http_client,
+    library::get_library,
+    track::{LiveStream, QueuedTrack, TrackData},
+    utils::{self, audio::DirectAudio},
+};
 use serenity::{
     all::{Guild, GuildId},
+    builder::CreateEmbed,
     cache::CacheRef,
     client::Context,
     model,
-    model::application::CommandInteraction,
+    model::{application::CommandInteraction, colour::Colour, id::UserId},
 };
 use songbird::{
-    input::{Input, YoutubeDl},
+    events::{Event, TrackEvent},
+    input::{Compose, File, HlsRequest, HttpRequest, Input, YoutubeDl},
     tracks::{Track, TrackHandle},
     Songbird,
 };
+use tokio::{sync::watch, task::JoinHandle};
 
 pub fn get_active_voice_channel_id(
     guild: CacheRef<'_, GuildId, Guild, Infallible>,
@@ -24,6 +39,46 @@ pub fn get_active_voice_channel_id(
         .and_then(|voice_state| voice_state.channel_id)
 }
 
+/// Users in the voice channel without bots
+pub fn get_voice_channel_listeners(
+    guild: CacheRef<'_, GuildId, Guild, Infallible>,
+    channel_id: model::id::ChannelId,
+) -> Vec<UserId> {
+    guild
+        .voice_states
+        .values()
+        .filter(|voice_state| voice_state.channel_id == Some(channel_id))
+        .filter(|voice_state| {
+            let is_bot = voice_state
+                .member
+                .as_ref()
+                .map(|member| member.user.bot)
+                .or_else(|| {
+                    guild
+                        .members
+                        .get(&voice_state.user_id)
+                        .map(|member| member.user.bot)
+                })
+                .unwrap_or(false);
+            !is_bot
+        })
+        .map(|voice_state| voice_state.user_id)
+        .collect()
+}
+
+/// Users that listen in the voice channel of the bot
+pub fn get_bot_listeners(ctx: &Context, guild_id: GuildId) -> Vec<UserId> {
+    ctx.cache
+        .guild(guild_id)
+        .and_then(|guild| get_active_voice_channel_id(guild, ctx.cache.current_user().id))
+        .and_then(|channel_id| {
+            ctx.cache
+                .guild(guild_id)
+                .map(|guild| get_voice_channel_listeners(guild, channel_id))
+        })
+        .unwrap_or_default()
+}
+
 pub async fn join(
     ctx: &Context,
     command: &CommandInteraction,
@@ -47,6 +102,10 @@ pub async fn join(
         })
         .ok_or(CadencyError::Join)?;
     debug!("Try to join guild with id: {:?}", guild_id);
+    // Remember where the music was requested to send notifications there
+    get_guild_states(ctx).await.update(guild_id, |state| {
+        state.text_channel = Some(command.channel_id)
+    });
     // Skip channel join if already connected
     if let Some(call) = manager.get(guild_id) {
         let has_current_connection = {
@@ -70,6 +129,7 @@ pub async fn join(
             error!("Voice channel join failed: {err:?}");
             CadencyError::Join
         })?;
+        register_voice_handlers(ctx, &mut locked_call, guild_id).await;
     }
     Ok((manager, call, guild_id))
 }
@@ -77,28 +137,592 @@ pub async fn join(
 pub async fn add_song(
     context: &Context,
     call: std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
     payload: String,
     is_url: bool,
-) -> Result<(songbird::input::AuxMetadata, TrackHandle), songbird::input::AuxMetadataError> {
+    requester: UserId,
+    playlist: Option<String>,
+) -> Result<(songbird::input::AuxMetadata, TrackHandle), CadencyError> {
     debug!("Add song to playlist: '{payload}'");
+    // Checked before the lookup as well, so a user at the limit doesn't wait for it
+    check_user_queue_limit(context, &call, guild_id, requester).await?;
+    let source = create_source(context, payload, is_url).await?;
+    enqueue_source(context, call, guild_id, source, requester, playlist).await
+}
+
+/// Add a song of an url whose direct audio was already detected, so the url is not asked again
+pub async fn add_detected_song(
+    context: &Context,
+    call: std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    url: String,
+    direct_audio: Option<DirectAudio>,
+    requester: UserId,
+    playlist: Option<String>,
+) -> Result<(songbird::input::AuxMetadata, TrackHandle), CadencyError> {
+    debug!("Add song to playlist: '{url}'");
+    // Checked before the lookup as well, so a user at the limit doesn't wait for it
+    check_user_queue_limit(context, &call, guild_id, requester).await?;
+    let source = create_detected_source(context, url, true, direct_audio).await?;
+    enqueue_source(context, call, guild_id, source, requester, playlist).await
+}
+
+async fn check_user_queue_limit(
+    context: &Context,
+    call: &std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    requester: UserId,
+) -> Result<(), CadencyError> {
+    let user_queue_limit = get_guild_states(context)
+        .await
+        .get(guild_id, |state| state.settings.user_queue_limit);
+    if let Some(limit) = user_queue_limit {
+        let handler = call.lock().await;
+        if utils::queue::count_user_tracks(handler.queue(), requester) >= limit {
+            return Err(CadencyError::UserQueueLimit { limit });
+        }
+    }
+    Ok(())
+}
+
+/// Add a created source to the queue once it passed the limits of the guild
+async fn enqueue_source(
+    context: &Context,
+    call: std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    (source, metadata, direct_audio): Source,
+    requester: UserId,
+    playlist: Option<String>,
+) -> Result<(songbird::input::AuxMetadata, TrackHandle), CadencyError> {
+    let guild_states = get_guild_states(context).await;
+    let fair_queue = guild_states.get(guild_id, |state| state.settings.fair_queue);
+    let is_live = direct_audio.as_ref().is_some_and(DirectAudio::is_live);
+    // Every track takes a slot of its requester, live streams included
+    check_user_queue_limit(context, &call, guild_id, requester).await?;
+    // Live streams never end, so their length doesn't count
+    if !is_live {
+        let is_file = matches!(direct_audio, Some(DirectAudio::File));
+        check_duration_limits(
+            context,
+            &call,
+            guild_id,
+            requester,
+            metadata.duration,
+            is_file,
+        )
+        .await?;
+    }
+    let mut handler = call.lock().await;
+
+    // Enqueue the source, the guild filter is applied once the track starts
+    let input: Input = FilteredInput::new(source, guild_states.clone(), guild_id)
+        .with_metadata(metadata.clone())
+        .into();
+
+    // Create track with metadata and requester as user data
+    let track_data = TrackData {
+        live: is_live.then(LiveStream::default),
+        ..TrackData::new(metadata.clone(), requester, playlist)
+    };
+    let track = Track::new_with_data(input, std::sync::Arc::new(track_data));
+    // The stream of the next track is resolved shortly before this track ends
+    let preload_time = metadata
+        .duration
+        .filter(|_| !is_live)
+        .map(|duration| duration.saturating_sub(PRELOAD_TIME));
+    let track_handle = handler.enqueue_with_preload(track, preload_time);
+    if fair_queue {
+        utils::queue::apply_fair_order(handler.queue());
+    }
+    // Remember the track in the guild history once it is finished
+    let _ = track_handle.add_event(
+        Event::Track(TrackEvent::End),
+        HistoryHandler {
+            guild_states,
+            guild_id,
+        },
+    );
+    // Streams are resolved once the track is about to play, they may not be available anymore
+    let _ = track_handle.add_event(
+        Event::Track(TrackEvent::Error),
+        UnavailableTrackHandler {
+            manager: get_songbird(context).await,
+            guild_id,
+        },
+    );
+    if !is_live {
+        let _ = track_handle.add_event(
+            Event::Track(TrackEvent::Play),
+            AutoplayHandler {
+                ctx: context.clone(),
+                guild_id,
+            },
+        );
+    }
+    if matches!(direct_audio, Some(DirectAudio::Radio { .. })) {
+        let _ = track_handle.add_event(
+            Event::Track(TrackEvent::Play),
+            StreamTitleHandler {
+                client: get_http_client(context).await,
+            },
+        );
+    }
+
+    Ok((metadata, track_handle))
+}
+
+/// Reject tracks that are longer than the song length limit of the guild or that would make
+/// the queue longer than its maximum duration. DJs are not limited.
+/// Tracks with unknown duration pass, except audio files whose length couldn't be read.
+async fn check_duration_limits(
+    context: &Context,
+    call: &std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    requester: UserId,
+    duration: Option<Duration>,
+    is_file: bool,
+) -> Result<(), CadencyError> {
+    let (song_length_limit, max_queue_duration) =
+        get_guild_states(context).await.get(guild_id, |state| {
+            (
+                state.settings.song_length_limit,
+                state.settings.max_queue_duration,
+            )
+        });
+    if duration.is_none() && !is_file
+        || song_length_limit.is_none() && max_queue_duration.is_none()
+        || utils::policy::is_dj_user(context, guild_id, requester).await
+    {
+        return Ok(());
+    }
+    // A file without known length could be longer than any limit
+    let duration = duration.ok_or(CadencyError::UnknownDuration)?;
+    if let Some(limit) = song_length_limit.filter(|limit| duration > *limit) {
+        return Err(CadencyError::SongTooLong { duration, limit });
+    }
+    if let Some(limit) = max_queue_duration {
+        let queued = {
+            let handler = call.lock().await;
+            utils::queue::remaining_duration(handler.queue()).await
+        };
+        if queued + duration > limit {
+            return Err(CadencyError::QueueTooLong { limit });
+        }
+    }
+    Ok(())
+}
+
+/// Format a duration like `3:45` or `1:02:03`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Embed that explains why a track was not added to the queue because of a limit
+pub fn rejection_embed(err: &CadencyError) -> Option<CreateEmbed> {
+    let description = match err {
+        CadencyError::SongTooLong { duration, limit } => format!(
+            "⏱️ **The track is too long!**\n\nThe track takes {}, but tracks can't be longer than {}.",
+            format_duration(*duration),
+            format_duration(*limit)
+        ),
+        CadencyError::QueueTooLong { limit } => format!(
+            "📜 **The queue is full!**\n\nThe queue can't be longer than {}, try again later.",
+            format_duration(*limit)
+        ),
+        CadencyError::UnknownDuration => {
+            "⏱️ **The length of the file is unknown!**\n\nOnly files with a known length can be added while the length is limited.".to_string()
+        }
+        CadencyError::UserQueueLimit { limit } => format!(
+            "📜 **Queue limit reached!**\n\nYou can only have {limit} tracks in the queue at the same time."
+        ),
+        _ => return None,
+    };
+    Some(
+        CreateEmbed::default()
+            .title("🚫 Track Rejected")
+            .color(Colour::from_rgb(178, 34, 34)) // Firebrick
+            .description(format!("{description}\n\n🎧 DJs are not limited.")),
+    )
+}
+
+/// Create the YoutubeDL source from url or search string
+async fn youtube_source(context: &Context, payload: String, is_url: bool) -> YoutubeDl<'static> {
     let request_client = get_http_client(context).await;
-    // Create the YoutubeDL source from url or search string
-    let source = if is_url {
+    if is_url {
         YoutubeDl::new(request_client, payload)
     } else {
         YoutubeDl::new(request_client, format!("ytsearch1:{payload}"))
+    }
+}
+
+/// How long before the end of a track the stream of the next track is resolved
+const PRELOAD_TIME: Duration = Duration::from_secs(20);
+
+/// Check if the url points to a YouTube video
+fn is_youtube_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .is_some_and(|host| {
+            host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com")
+        })
+}
+
+/// Metadata of a queued YouTube track that is complete enough to skip the lookup with yt-dlp,
+/// like the entries of a fetched playlist. Only verified tracks are trusted, otherwise
+/// a made up duration would pass the limits of the guild.
+fn known_metadata(track: &QueuedTrack) -> Option<songbird::input::AuxMetadata> {
+    if !track.verified {
+        return None;
+    }
+    let url = track.url.as_ref().filter(|url| is_youtube_url(url))?;
+    Some(songbird::input::AuxMetadata {
+        title: Some(track.title.clone()?),
+        duration: Some(track.duration.filter(|duration| !duration.is_zero())?),
+        source_url: Some(url.clone()),
+        ..Default::default()
+    })
+}
+
+/// Create the source of a queued track. Tracks with known metadata are resolved lazily,
+/// yt-dlp only looks up their stream once they are about to play.
+async fn create_track_source(
+    context: &Context,
+    track: QueuedTrack,
+) -> Result<Source, CadencyError> {
+    if let Some(metadata) = known_metadata(&track) {
+        debug!("Resolve '{:?}' once it plays", metadata.title);
+        let url = metadata.source_url.clone().unwrap_or_default();
+        let source = YoutubeDl::new(get_http_client(context).await, url);
+        return Ok((Box::new(source), metadata, None));
+    }
+    let (payload, is_url) = track.payload().ok_or(CadencyError::Command {
+        message: "❌ **The track has neither a title nor an url**".to_string(),
+    })?;
+    create_source(context, payload, is_url).await
+}
+
+/// Source of a track with its metadata and how the audio is streamed
+type Source = (
+    Box<dyn Compose>,
+    songbird::input::AuxMetadata,
+    Option<DirectAudio>,
+);
+
+/// Create the source of a track together with its metadata.
+/// Library files are read from disk, urls of audio files and radio streams are streamed
+/// directly, everything else is resolved by yt-dlp.
+async fn create_source(
+    context: &Context,
+    payload: String,
+    is_url: bool,
+) -> Result<Source, CadencyError> {
+    if is_url && payload.starts_with("file:") {
+        let track =
+            get_library(context)
+                .await
+                .get_by_url(&payload)
+                .ok_or(CadencyError::Command {
+                    message: "❌ **The file is not part of the music library**".to_string(),
+                })?;
+        debug!("Play library file: '{}'", track.id);
+        let metadata = track.metadata();
+        return Ok((Box::new(File::new(track.path)), metadata, None));
+    }
+    let direct_audio = if is_url {
+        detect_direct_audio(context, &payload).await
+    } else {
+        None
     };
-    let mut handler = call.lock().await;
+    create_detected_source(context, payload, is_url, direct_audio).await
+}
 
-    // Extract metadata and enqueue the source
-    let mut input: Input = source.into();
-    let metadata = input.aux_metadata().await?;
+/// Create the source of a track whose direct audio is already known
+async fn create_detected_source(
+    context: &Context,
+    payload: String,
+    is_url: bool,
+    direct_audio: Option<DirectAudio>,
+) -> Result<Source, CadencyError> {
+    let request_client = get_http_client(context).await;
+    let (source, metadata): (Box<dyn Compose>, _) = match &direct_audio {
+        Some(DirectAudio::File) => {
+            debug!("Stream audio file directly: '{payload}'");
+            let metadata =
+                utils::audio::probe_metadata(request_client.clone(), payload.clone()).await?;
+            (
+                Box::new(HttpRequest::new(request_client, payload)),
+                metadata,
+            )
+        }
+        Some(DirectAudio::Radio { name }) => {
+            debug!("Stream radio directly: '{payload}'");
+            let metadata = utils::audio::live_metadata(payload.clone(), name.clone());
+            (
+                Box::new(HttpRequest::new(request_client, payload)),
+                metadata,
+            )
+        }
+        Some(DirectAudio::Hls) => {
+            debug!("Stream HTTP live stream directly: '{payload}'");
+            let metadata = utils::audio::live_metadata(payload.clone(), None);
+            (Box::new(HlsRequest::new(request_client, payload)), metadata)
+        }
+        None => {
+            let mut source = youtube_source(context, payload, is_url).await;
+            let metadata = source.aux_metadata().await?;
+            (Box::new(source), metadata)
+        }
+    };
+    Ok((source, metadata, direct_audio))
+}
 
-    // Create track with metadata as user data
-    let track = Track::new_with_data(input, std::sync::Arc::new(metadata.clone()));
-    let track_handle = handler.enqueue(track).await;
+/// Look up the metadata of a track without adding it to the queue
+pub async fn fetch_metadata(
+    context: &Context,
+    payload: String,
+    is_url: bool,
+) -> Result<songbird::input::AuxMetadata, CadencyError> {
+    let (_, metadata, _) = create_source(context, payload, is_url).await?;
+    Ok(metadata)
+}
 
-    Ok((metadata, track_handle))
+/// Ask the server of the url if it serves audio directly, library files never do
+pub async fn detect_direct_audio(context: &Context, url: &str) -> Option<DirectAudio> {
+    if url.starts_with("file:") {
+        return None;
+    }
+    let request_client = get_http_client(context).await;
+    utils::audio::detect_direct_audio(&request_client, url).await
+}
+
+/// What an url points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlKind {
+    /// Several tracks, like a playlist, a set or an album
+    Playlist,
+    /// A single track, with the audio that the url serves directly
+    Track(Option<DirectAudio>),
+}
+
+/// YouTube videos without a `list` parameter are single tracks
+fn is_single_video_url(url: &str) -> bool {
+    let has_list =
+        reqwest::Url::parse(url).is_ok_and(|url| url.query_pairs().any(|(key, _)| key == "list"));
+    !has_list && cadency_yt_playlist::video_id(url).is_some()
+}
+
+/// Check if the url resolves to several tracks or to a single track.
+/// Library files, YouTube videos and urls that serve audio directly are single tracks.
+pub async fn classify_url(context: &Context, url: &str) -> UrlKind {
+    if url.starts_with("file:") || is_single_video_url(url) {
+        return UrlKind::Track(None);
+    }
+    if let Some(direct_audio) = detect_direct_audio(context, url).await {
+        return UrlKind::Track(Some(direct_audio));
+    }
+    let playlist_url = url.to_string();
+    // yt-dlp blocks until the first entries are resolved
+    let is_playlist =
+        match tokio::task::spawn_blocking(move || cadency_yt_playlist::is_playlist(&playlist_url))
+            .await
+        {
+            Ok(Ok(is_playlist)) => is_playlist,
+            Ok(Err(err)) => {
+                debug!("Unable to check if '{url}' is a playlist: {err}");
+                false
+            }
+            Err(err) => {
+                error!("❌ Playlist check failed: {err}");
+                false
+            }
+        };
+    if is_playlist {
+        UrlKind::Playlist
+    } else {
+        UrlKind::Track(None)
+    }
+}
+
+/// Result of adding several tracks to the queue at once
+#[derive(Debug, Default, Clone)]
+pub struct EnqueueSummary {
+    pub added: usize,
+    pub duration: Duration,
+    pub skipped_by_limit: usize,
+    pub skipped_unavailable: usize,
+    /// Tracks that are not added yet
+    pub pending: usize,
+    /// The queue was cleared before all tracks were added
+    pub cancelled: bool,
+}
+
+impl EnqueueSummary {
+    /// Add up the results of two parts of the same request
+    pub fn combine(&mut self, other: &EnqueueSummary) {
+        self.added += other.added;
+        self.duration += other.duration;
+        self.skipped_by_limit += other.skipped_by_limit;
+        self.skipped_unavailable += other.skipped_unavailable;
+        self.pending = other.pending;
+        self.cancelled |= other.cancelled;
+    }
+
+    /// Human readable summary for command responses
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "✅ **Added {} song{} to the queue**\n⏱️ **Total Duration:** {:.1} minutes",
+            self.added,
+            if self.added == 1 { "" } else { "s" },
+            self.duration.as_secs_f32() / 60_f32
+        );
+        if self.skipped_by_limit > 0 {
+            description.push_str(&format!(
+                "\n⚠️ **Skipped:** {} song{} (exceeded limits)",
+                self.skipped_by_limit,
+                if self.skipped_by_limit == 1 { "" } else { "s" }
+            ));
+        }
+        if self.skipped_unavailable > 0 {
+            description.push_str(&format!(
+                "\n🚫 **Unavailable:** {} song{} (removed or restricted)",
+                self.skipped_unavailable,
+                if self.skipped_unavailable == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            ));
+        }
+        if self.cancelled {
+            description.push_str("\n⏹️ **Stopped:** the queue was cleared while loading");
+        } else if self.pending > 0 {
+            description.push_str(&format!(
+                "\n⏳ **Loading:** {} more song{}...",
+                self.pending,
+                if self.pending == 1 { "" } else { "s" }
+            ));
+        }
+        description
+    }
+}
+
+async fn add_created_source(
+    context: &Context,
+    call: std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    track: QueuedTrack,
+    source: JoinHandle<Result<Source, CadencyError>>,
+) -> Result<(songbird::input::AuxMetadata, TrackHandle), CadencyError> {
+    let source = source
+        .await
+        .map_err(|err| CadencyError::Runtime(format!("Failed to create source: {err}")))??;
+    enqueue_source(
+        context,
+        call,
+        guild_id,
+        source,
+        track.requester,
+        track.playlist,
+    )
+    .await
+}
+
+/// Amount of tracks whose source is created at the same time by `add_songs`
+const ADD_SONGS_CONCURRENCY: usize = 4;
+
+/// Add the tracks to the queue in their order.
+/// The sources of the next tracks are created concurrently while earlier tracks are added.
+/// Tracks beyond the song limit or beyond the limits of the guild are skipped,
+/// tracks without title and url count as unavailable.
+/// Adding stops when the queue of the guild is cleared, the progress is sent after every track.
+pub async fn add_songs(
+    context: &Context,
+    call: std::sync::Arc<serenity::prelude::Mutex<songbird::Call>>,
+    guild_id: GuildId,
+    tracks: Vec<QueuedTrack>,
+    song_limit: usize,
+    progress: Option<&watch::Sender<EnqueueSummary>>,
+) -> EnqueueSummary {
+    let guild_states = get_guild_states(context).await;
+    let generation = guild_states.get(guild_id, |state| state.queue_generation);
+    let mut summary = EnqueueSummary {
+        pending: tracks.len(),
+        ..Default::default()
+    };
+    let mut tracks = tracks.into_iter();
+    let mut sources: VecDeque<(QueuedTrack, JoinHandle<Result<Source, CadencyError>>)> =
+        VecDeque::new();
+    loop {
+        // Tracks beyond the song limit are skipped without looking them up
+        if summary.added >= song_limit {
+            sources.iter().for_each(|(_, source)| source.abort());
+            summary.skipped_by_limit += sources.len() + tracks.len();
+            summary.pending = 0;
+            break;
+        }
+        // Keep creating the sources of the next tracks
+        while sources.len() < ADD_SONGS_CONCURRENCY {
+            let Some(track) = tracks.next() else {
+                break;
+            };
+            // Saved tracks without title and url can't be looked up
+            if track.payload().is_none() {
+                summary.pending -= 1;
+                summary.skipped_unavailable += 1;
+                continue;
+            }
+            let context = context.clone();
+            let lookup = track.clone();
+            let source = tokio::spawn(async move { create_track_source(&context, lookup).await });
+            sources.push_back((track, source));
+        }
+        let Some((track, source)) = sources.pop_front() else {
+            break;
+        };
+        summary.pending -= 1;
+        if guild_states.get(guild_id, |state| state.queue_generation) != generation {
+            debug!("Queue was cleared, stop adding songs");
+            summary.cancelled = true;
+            summary.pending = 0;
+            source.abort();
+            sources.iter().for_each(|(_, source)| source.abort());
+            break;
+        }
+        let duration = track.duration;
+        let added = add_created_source(context, call.clone(), guild_id, track, source).await;
+        match added {
+            Ok((added_song_meta, _)) => {
+                summary.added += 1;
+                summary.duration += added_song_meta.duration.or(duration).unwrap_or_default();
+                debug!("➕ Added song '{:?}' to the queue", added_song_meta.title);
+            }
+            Err(
+                CadencyError::UserQueueLimit { .. }
+                | CadencyError::SongTooLong { .. }
+                | CadencyError::QueueTooLong { .. }
+                | CadencyError::UnknownDuration,
+            ) => summary.skipped_by_limit += 1,
+            Err(err) => {
+                error!("❌ Failed to add song: {err}");
+                summary.skipped_unavailable += 1;
+            }
+        }
+        if let Some(progress) = progress {
+            progress.send_replace(summary.clone());
+        }
+    }
+    summary
 }
 
 pub async fn get_songbird(ctx: &Context) -> std::sync::Arc<songbird::Songbird> {
@@ -106,3 +730,69 @@ pub async fn get_songbird(ctx: &Context) -> std::sync::Arc<songbird::Songbird> {
         .await
         .expect("Failed to get songbird manager")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn queued_track(url: &str, duration: Option<Duration>) -> QueuedTrack {
+        QueuedTrack {
+            title: Some("Hey Jude".to_string()),
+            url: Some(url.to_string()),
+            requester: UserId::new(1),
+            playlist: None,
+            duration,
+            verified: true,
+        }
+    }
+
+    #[test]
+    fn should_use_known_metadata_of_youtube_tracks() {
+        let duration = Some(Duration::from_secs(431));
+        let metadata = known_metadata(&queued_track(
+            "https://www.youtube.com/watch?v=A_MjCqQoLLA",
+            duration,
+        ))
+        .unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Hey Jude"));
+        assert_eq!(metadata.duration, duration);
+        assert!(known_metadata(&queued_track("https://youtu.be/A_MjCqQoLLA", duration)).is_some());
+    }
+
+    #[test]
+    fn should_look_up_incomplete_or_other_tracks() {
+        assert!(known_metadata(&queued_track("https://youtu.be/A_MjCqQoLLA", None)).is_none());
+        let duration = Some(Duration::from_secs(180));
+        assert!(known_metadata(&queued_track("https://example.com/song.mp3", duration)).is_none());
+        assert!(known_metadata(&queued_track("https://notyoutube.com/watch", duration)).is_none());
+    }
+
+    #[test]
+    fn should_only_treat_videos_without_list_as_single() {
+        assert!(is_single_video_url(
+            "https://www.youtube.com/watch?v=A_MjCqQoLLA"
+        ));
+        assert!(is_single_video_url("https://youtu.be/A_MjCqQoLLA?t=42"));
+        assert!(!is_single_video_url(
+            "https://www.youtube.com/watch?v=A_MjCqQoLLA&list=PL123"
+        ));
+        assert!(!is_single_video_url(
+            "https://www.youtube.com/playlist?list=PL123"
+        ));
+        assert!(!is_single_video_url(
+            "https://soundcloud.com/artist/sets/album"
+        ));
+    }
+
+    #[test]
+    fn should_look_up_tracks_that_are_not_verified() {
+        let track = QueuedTrack {
+            verified: false,
+            ..queued_track(
+                "https://www.youtube.com/watch?v=A_MjCqQoLLA",
+                Some(Duration::from_secs(1)),
+            )
+        };
+        assert!(known_metadata(&track).is_none());
+    }
+}
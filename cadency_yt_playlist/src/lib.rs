@@ -7,4 +7,5 @@ mod playlist;
 mod ytdlp;
 
 pub use error::YtPlaylistError;
+pub use models::{PlaylistItem, SearchItem};
 pub use playlist::*;
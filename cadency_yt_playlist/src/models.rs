@@ -12,13 +12,50 @@ pub struct YtDlpResponse<T: de::DeserializeOwned> {
     pub messages: Vec<Message>,
 }
 
+/// Entry of a playlist, set or album.
+/// Flat entries of some sites only know their url, the rest is looked up when they play.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PlaylistItem {
     pub id: String,
+    #[serde(default)]
     pub playlist_index: usize,
-    pub duration: f32,
+    pub duration: Option<f32>,
+    pub title: Option<String>,
+    pub url: String,
+}
+
+/// Overview of whatever an url resolves to, only the first entries are fetched
+#[derive(Deserialize, Debug)]
+pub(crate) struct UrlInfo {
+    #[serde(rename = "_type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<serde_json::Value>,
+}
+
+impl UrlInfo {
+    pub fn has_multiple_entries(&self) -> bool {
+        self.kind.as_deref() == Some("playlist") && self.entries.len() > 1
+    }
+}
+
+/// Result of a YouTube search
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchItem {
+    pub id: String,
     pub title: String,
     pub url: String,
+    /// Live streams have no duration
+    pub duration: Option<f32>,
+    pub channel: Option<String>,
+    pub uploader: Option<String>,
+}
+
+impl SearchItem {
+    /// Name of the channel that uploaded the video
+    pub fn channel_name(&self) -> Option<&str> {
+        self.channel.as_deref().or(self.uploader.as_deref())
+    }
 }
 
 #[cfg(test)]
@@ -30,4 +67,34 @@ mod test {
         let file = std::fs::read_to_string("./test/data/playlist_entry.json").unwrap();
         let _ = serde_json::from_str::<PlaylistItem>(&file).unwrap();
     }
+
+    #[test]
+    fn should_parse_entry_with_url_only() {
+        let item = serde_json::from_str::<PlaylistItem>(
+            r#"{"_type": "url", "id": "1", "url": "https://api.soundcloud.com/tracks/1"}"#,
+        )
+        .unwrap();
+        assert_eq!(item.title, None);
+        assert_eq!(item.duration, None);
+    }
+
+    #[test]
+    fn should_detect_multiple_entries() {
+        let file = std::fs::read_to_string("./test/data/set_info.json").unwrap();
+        let info = serde_json::from_str::<UrlInfo>(&file).unwrap();
+        assert!(info.has_multiple_entries());
+        let info = serde_json::from_str::<UrlInfo>(
+            r#"{"_type": "video", "id": "eH4F1Tdb040", "title": "Stephen - Crossfire"}"#,
+        )
+        .unwrap();
+        assert!(!info.has_multiple_entries());
+    }
+
+    #[test]
+    fn should_parse_search_entry() {
+        let file = std::fs::read_to_string("./test/data/search_entry.json").unwrap();
+        let item = serde_json::from_str::<SearchItem>(&file).unwrap();
+        assert_eq!(item.channel_name(), Some("Stephen"));
+        assert_eq!(item.duration, Some(272.0));
+    }
 }
@@ -1,6 +1,8 @@
+use serde::de::DeserializeOwned;
+
 use crate::{
     error::YtPlaylistError,
-    models::{Message, PlaylistItem, YtDlpResponse},
+    models::{Message, PlaylistItem, SearchItem, UrlInfo, YtDlpResponse},
     ytdlp::YtDlp,
 };
 
@@ -12,22 +14,106 @@ pub fn fetch_playlist_songs(
         .arg("-j".to_string())
         .arg(playlist_url)
         .execute()?;
+    parse_entries(ytdl_output.stdout)
+}
+
+/// Ask yt-dlp if the url resolves to more than one entry, like a playlist, a set or an album.
+/// Urls that yt-dlp doesn't support are no playlists.
+pub fn is_playlist(url: &str) -> Result<bool, YtPlaylistError> {
+    let ytdl_output = YtDlp::new()
+        .arg("--flat-playlist".to_string())
+        .arg("-J".to_string())
+        .arg("--playlist-end".to_string())
+        .arg("2".to_string())
+        .arg(url.to_string())
+        .execute()?;
+    if !ytdl_output.status.success() {
+        return Ok(false);
+    }
+    let info: UrlInfo = serde_json::from_slice(&ytdl_output.stdout).map_err(|err| {
+        YtPlaylistError::Deserializing {
+            message: err.to_string(),
+            data: String::from_utf8_lossy(&ytdl_output.stdout).to_string(),
+            source: err,
+        }
+    })?;
+    Ok(info.has_multiple_entries())
+}
+
+/// Search YouTube for the query and return up to `amount` results
+pub fn search_songs(
+    query: String,
+    amount: usize,
+) -> Result<YtDlpResponse<Vec<SearchItem>>, YtPlaylistError> {
+    let ytdl_output = YtDlp::new()
+        .arg("--flat-playlist".to_string())
+        .arg("-j".to_string())
+        .arg(format!("ytsearch{amount}:{query}"))
+        .execute()?;
+    parse_entries(ytdl_output.stdout)
+}
 
+/// Fetch up to `amount` songs of the YouTube Mix of a video, the mix starts with the video itself
+pub fn fetch_related_songs(
+    video_id: &str,
+    amount: usize,
+) -> Result<YtDlpResponse<Vec<PlaylistItem>>, YtPlaylistError> {
+    let ytdl_output = YtDlp::new()
+        .arg("--flat-playlist".to_string())
+        .arg("-j".to_string())
+        .arg("--playlist-end".to_string())
+        .arg(amount.to_string())
+        .arg(format!(
+            "https://www.youtube.com/watch?v={video_id}&list=RD{video_id}"
+        ))
+        .execute()?;
+    parse_entries(ytdl_output.stdout)
+}
+
+/// Extract the id of a YouTube video from its url
+pub fn video_id(url: &str) -> Option<String> {
+    let url = url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_start_matches("m.")
+        .trim_start_matches("music.");
+    let (host, path) = url.split_once('/')?;
+    let id = match host {
+        "youtu.be" => path.split(['?', '&', '#']).next(),
+        "youtube.com" => match path.split_once('?') {
+            Some(("watch", query)) => query
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("v=")),
+            _ => path
+                .strip_prefix("shorts/")
+                .and_then(|id| id.split(['?', '#']).next()),
+        },
+        _ => None,
+    }?;
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Parse the json lines of yt-dlp, lines that can't be parsed are returned as messages
+fn parse_entries<T: DeserializeOwned>(
+    stdout: Vec<u8>,
+) -> Result<YtDlpResponse<Vec<T>>, YtPlaylistError> {
     let mut failed_deserializing: Vec<Message> = Vec::new();
-    let deserialized_items: Vec<PlaylistItem> = String::from_utf8(ytdl_output.stdout)?
+    let deserialized_items: Vec<T> = String::from_utf8(stdout)?
         .split('\n')
         .filter_map(|string_output| {
             if string_output.is_empty() {
                 None
             } else {
                 Some(
-                    serde_json::from_slice::<PlaylistItem>(string_output.as_bytes()).map_err(
-                        |err| YtPlaylistError::Deserializing {
+                    serde_json::from_slice::<T>(string_output.as_bytes()).map_err(|err| {
+                        YtPlaylistError::Deserializing {
                             message: err.to_string(),
                             data: string_output.to_string(),
                             source: err,
-                        },
-                    ),
+                        }
+                    }),
                 )
             }
         })
@@ -69,4 +155,22 @@ mod test {
         .unwrap();
         assert_eq!(response.messages.len(), 0, "Expect no deserializing errors")
     }
+
+    #[test]
+    fn should_extract_video_id() {
+        for url in [
+            "https://www.youtube.com/watch?v=BJ8XPi-cPkM",
+            "https://youtube.com/watch?list=PLDfKAXSi6kUZbsoz3AcUYjy8n6hbulk4o&v=BJ8XPi-cPkM",
+            "https://youtu.be/BJ8XPi-cPkM?t=42",
+            "https://music.youtube.com/watch?v=BJ8XPi-cPkM",
+            "https://www.youtube.com/shorts/BJ8XPi-cPkM",
+        ] {
+            assert_eq!(video_id(url).as_deref(), Some("BJ8XPi-cPkM"), "{url}");
+        }
+        assert_eq!(
+            video_id("https://www.youtube.com/playlist?list=PLDfKAXSi6kU"),
+            None
+        );
+        assert_eq!(video_id("https://example.com/watch?v=BJ8XPi-cPkM"), None);
+    }
 }